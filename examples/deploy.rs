@@ -60,7 +60,15 @@ fn main() -> anyhow::Result<()> {
             base: BaseInstantiateMsg {
                 ans_host_address: abstract_deployment.ans_host.addr_str()?,
             },
-            module: AppInstantiateMsg {},
+            module: AppInstantiateMsg {
+                factory_addr: None,
+                croncat_version: None,
+                creator_filter: None,
+                action_policy: None,
+                spend_limit: None,
+                pauser: None,
+                tag_rules: None,
+            },
         },
         None,
     )?;
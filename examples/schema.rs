@@ -1,11 +1,11 @@
-use app::contract::CroncatApp as App;
 use cosmwasm_schema::remove_schemas;
+use croncat_app::contract::CroncatApp as App;
 use std::env::current_dir;
 use std::fs::create_dir_all;
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
-    out_dir.push("../schema");
+    out_dir.push("schema");
     create_dir_all(&out_dir).unwrap();
     remove_schemas(&out_dir).unwrap();
 
@@ -1,26 +1,41 @@
-use abstract_core::objects::module::ModuleId;
+use abstract_core::adapter::AdapterRequestMsg;
+use abstract_core::objects::{module::ModuleId, AnsAsset, AssetEntry, PoolAddress, PoolReference};
 use abstract_sdk::{
-    features::{AccountIdentification, Dependencies},
-    AbstractSdkResult,
+    features::{AbstractNameService, AccountIdentification, Dependencies},
+    AbstractSdkError, AbstractSdkResult,
 };
 use abstract_sdk::{AppInterface, ModuleInterface};
-use cosmwasm_std::{Addr, CosmosMsg, Deps};
-use croncat_integration_utils::CronCatTaskRequest;
+use cosmwasm_std::{
+    to_binary, wasm_execute, Addr, Binary, Coin, CosmosMsg, Decimal, Deps, StdResult, WasmQuery,
+};
+use croncat_integration_utils::{
+    CronCatAction, CronCatCosmosQuery, CronCatInterval, CronCatPathToValue, CronCatTaskRequest,
+    CronCatTransform, CronCatValueIndex,
+};
 use croncat_sdk_manager::types::TaskBalanceResponse;
 use croncat_sdk_tasks::types::TaskResponse;
-use cw_asset::AssetListUnchecked;
+use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_asset::{AssetInfo, AssetListUnchecked};
 
 use crate::contract::CRONCAT_ID;
-use crate::msg::{AppExecuteMsg, AppQueryMsg};
+use crate::msg::{
+    ActiveTasksByCreatorResponse, ActiveTasksResponse, AppExecuteMsg, AppQueryMsg, AppReceiveMsg,
+    CreationMode, ExecuteMsg, GroupSplit, GroupTasksResponse, PreviewScheduleResponse,
+    TaskCostResponse,
+};
+use crate::state::{TaskMeta, TAG_SEPARATOR};
 
 // Entry for the cron_cat factory address, stored in the ANS
 pub const CRON_CAT_FACTORY: &str = "croncat:factory";
 
+// Id of the Abstract DEX adapter
+pub const DEX_ADAPTER_ID: &str = "abstract:dex";
+
 // API for Abstract SDK users
 /// Interact with the cron_cat adapter in your module.
 pub trait CronCatInterface: AccountIdentification + Dependencies {
     /// Construct a new cron_cat interface
-    fn cron_cat<'a>(&'a self, deps: Deps<'a>) -> CronCat<Self> {
+    fn cron_cat<'a>(&'a self, deps: Deps<'a>) -> CronCat<'a, Self> {
         CronCat {
             base: self,
             deps,
@@ -59,6 +74,129 @@ impl<'a, T: CronCatInterface> CronCat<'a, T> {
                 task: Box::new(task),
                 task_tag: task_tag.into(),
                 assets,
+                mode: None,
+                group: None,
+            },
+        )
+    }
+
+    /// Create task, funded by the calling module with `funds` on top of the proxy `assets`
+    pub fn create_task_with_funds(
+        &self,
+        task: CronCatTaskRequest,
+        task_tag: impl Into<String>,
+        assets: AssetListUnchecked,
+        funds: Vec<Coin>,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        let modules = self.base.modules(self.deps);
+        modules.assert_module_dependency(self.module_id)?;
+        let msg = ExecuteMsg::from(AppExecuteMsg::CreateTask {
+            task: Box::new(task),
+            task_tag: task_tag.into(),
+            assets,
+            mode: None,
+            group: None,
+        });
+        let app_address = modules.module_address(self.module_id)?;
+        Ok(wasm_execute(app_address, &msg, funds)?.into())
+    }
+
+    /// Create task, funded by the calling module with `cw20` on top of the proxy `assets`
+    pub fn create_task_with_cw20(
+        &self,
+        task: CronCatTaskRequest,
+        task_tag: impl Into<String>,
+        assets: AssetListUnchecked,
+        cw20: Cw20Coin,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        let modules = self.base.modules(self.deps);
+        modules.assert_module_dependency(self.module_id)?;
+        let msg = to_binary(&AppReceiveMsg::CreateTask {
+            task: Box::new(task),
+            task_tag: task_tag.into(),
+            assets,
+            mode: None,
+            group: None,
+        })?;
+        let app_address = modules.module_address(self.module_id)?;
+        let send = Cw20ExecuteMsg::Send {
+            contract: app_address.into_string(),
+            amount: cw20.amount,
+            msg,
+        };
+        Ok(wasm_execute(cw20.address, &send, vec![])?.into())
+    }
+
+    /// Create task as a member of the group
+    pub fn create_task_in_group(
+        &self,
+        task: CronCatTaskRequest,
+        task_tag: impl Into<String>,
+        group: impl Into<String>,
+        assets: AssetListUnchecked,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.base.apps(self.deps).request(
+            self.module_id,
+            AppExecuteMsg::CreateTask {
+                task: Box::new(task),
+                task_tag: task_tag.into(),
+                assets,
+                mode: None,
+                group: Some(group.into()),
+            },
+        )
+    }
+
+    /// Create task from the registered template
+    pub fn create_task_from_template(
+        &self,
+        template: impl Into<String>,
+        params: Vec<(String, String)>,
+        task_tag: impl Into<String>,
+        assets: AssetListUnchecked,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.base.apps(self.deps).request(
+            self.module_id,
+            AppExecuteMsg::CreateTaskFromTemplate {
+                template: template.into(),
+                params,
+                task_tag: task_tag.into(),
+                assets,
+            },
+        )
+    }
+
+    /// Register json task template with `{{name}}` placeholders
+    pub fn register_template(
+        &self,
+        name: impl Into<String>,
+        task: Binary,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.base.apps(self.deps).request(
+            self.module_id,
+            AppExecuteMsg::RegisterTemplate {
+                name: name.into(),
+                task,
+            },
+        )
+    }
+
+    /// Create task, with the given behavior if the task tag is taken
+    pub fn create_task_with_mode(
+        &self,
+        task: CronCatTaskRequest,
+        task_tag: impl Into<String>,
+        assets: AssetListUnchecked,
+        mode: CreationMode,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.base.apps(self.deps).request(
+            self.module_id,
+            AppExecuteMsg::CreateTask {
+                task: Box::new(task),
+                task_tag: task_tag.into(),
+                assets,
+                mode: Some(mode),
+                group: None,
             },
         )
     }
@@ -74,6 +212,7 @@ impl<'a, T: CronCatInterface> CronCat<'a, T> {
             AppExecuteMsg::RefillTask {
                 task_tag: task_tag.into(),
                 assets,
+                creator: None,
             },
         )
     }
@@ -83,15 +222,178 @@ impl<'a, T: CronCatInterface> CronCat<'a, T> {
             self.module_id,
             AppExecuteMsg::RemoveTask {
                 task_tag: task_tag.into(),
+                creator: None,
             },
         )
     }
 
-    pub fn purge(&self, task_tags: Vec<String>) -> AbstractSdkResult<CosmosMsg> {
-        self.base
-            .apps(self.deps)
-            .request(self.module_id, AppExecuteMsg::Purge { task_tags })
+    /// Remove all tasks of the group
+    pub fn remove_group(&self, group: impl Into<String>) -> AbstractSdkResult<CosmosMsg> {
+        self.base.apps(self.deps).request(
+            self.module_id,
+            AppExecuteMsg::RemoveGroup {
+                group: group.into(),
+            },
+        )
+    }
+
+    /// Refill all tasks of the group, splitting assets between them
+    pub fn refill_group(
+        &self,
+        group: impl Into<String>,
+        assets: AssetListUnchecked,
+        split: GroupSplit,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.base.apps(self.deps).request(
+            self.module_id,
+            AppExecuteMsg::RefillGroup {
+                group: group.into(),
+                assets,
+                split,
+            },
+        )
     }
+
+    /// Set lifetime deposit cap of the task
+    pub fn set_budget(
+        &self,
+        task_tag: impl Into<String>,
+        cap: Option<AssetListUnchecked>,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.base.apps(self.deps).request(
+            self.module_id,
+            AppExecuteMsg::SetBudget {
+                task_tag: task_tag.into(),
+                cap,
+            },
+        )
+    }
+
+    pub fn adopt_task(
+        &self,
+        task_hash: impl Into<String>,
+        task_tag: impl Into<String>,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.base.apps(self.deps).request(
+            self.module_id,
+            AppExecuteMsg::AdoptTask {
+                task_hash: task_hash.into(),
+                task_tag: task_tag.into(),
+            },
+        )
+    }
+
+    pub fn purge(&self, task_tags: Vec<String>, recover: bool) -> AbstractSdkResult<CosmosMsg> {
+        self.base.apps(self.deps).request(
+            self.module_id,
+            AppExecuteMsg::Purge {
+                task_tags,
+                recover,
+                creator: None,
+            },
+        )
+    }
+
+    /// Create recurring task swapping `offer_asset` to `ask_asset` on the `dex`,
+    /// tagged with [`dca_task_tag`].
+    /// Swap is requested from the Abstract DEX adapter on behalf of the proxy,
+    /// so croncat manager has to be an authorized address of the adapter for this account.
+    /// Croncat requires `gas_limit` for the swap action
+    pub fn create_dca_task(
+        &self,
+        offer_asset: AnsAsset,
+        ask_asset: AssetEntry,
+        interval: CronCatInterval,
+        dex: impl Into<String>,
+        gas_limit: u64,
+        assets: AssetListUnchecked,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        let dex = dex.into();
+        let task_tag = dca_task_tag(&offer_asset.name, &ask_asset, &dex);
+        let dex_addr = self
+            .base
+            .modules(self.deps)
+            .module_address(DEX_ADAPTER_ID)?;
+        let task = dca_task_request(
+            dex_addr,
+            self.base.proxy_address(self.deps)?,
+            offer_asset,
+            ask_asset,
+            interval,
+            dex,
+            gas_limit,
+        )?;
+        self.create_task(task, task_tag, assets)
+    }
+}
+
+/// Task request of the DCA task created by [`CronCat::create_dca_task`]
+pub fn dca_task_request(
+    dex_addr: Addr,
+    proxy_addr: Addr,
+    offer_asset: AnsAsset,
+    ask_asset: AssetEntry,
+    interval: CronCatInterval,
+    dex: String,
+    gas_limit: u64,
+) -> StdResult<CronCatTaskRequest> {
+    let swap_msg = AdapterRequestMsg {
+        proxy_address: Some(proxy_addr.into_string()),
+        request: DexExecuteMsg::Action {
+            dex,
+            action: DexAction::Swap {
+                offer_asset,
+                ask_asset,
+                max_spread: None,
+                belief_price: None,
+            },
+        },
+    };
+    Ok(CronCatTaskRequest {
+        interval,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![CronCatAction {
+            msg: wasm_execute(dex_addr, &DexAdapterExecuteMsg::Module(swap_msg), vec![])?.into(),
+            gas_limit: Some(gas_limit),
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    })
+}
+
+/// Task tag of the DCA task created by [`CronCat::create_dca_task`].
+/// Names are split into segments of ascii alphanumerics, so the tag passes any tag rules,
+/// e.g. `juno>atom` becomes `juno/atom`
+pub fn dca_task_tag(offer_asset: &AssetEntry, ask_asset: &AssetEntry, dex: &str) -> String {
+    let segments: Vec<&str> = [dex, offer_asset.as_str(), ask_asset.as_str()]
+        .into_iter()
+        .flat_map(|name| name.split(|c: char| !c.is_ascii_alphanumeric()))
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    format!(
+        "dca{TAG_SEPARATOR}{}",
+        segments.join(&TAG_SEPARATOR.to_string())
+    )
+}
+
+// Swap request of the Abstract DEX adapter
+type DexAdapterExecuteMsg = abstract_core::adapter::ExecuteMsg<DexExecuteMsg>;
+
+#[cosmwasm_schema::cw_serde]
+enum DexExecuteMsg {
+    Action { dex: String, action: DexAction },
+}
+
+#[cosmwasm_schema::cw_serde]
+enum DexAction {
+    Swap {
+        offer_asset: AnsAsset,
+        ask_asset: AssetEntry,
+        max_spread: Option<Decimal>,
+        belief_price: Option<Decimal>,
+    },
 }
 
 impl<'a, T: CronCatInterface> CronCat<'a, T> {
@@ -131,7 +433,7 @@ impl<'a, T: CronCatInterface> CronCat<'a, T> {
         start_after: Option<(String, String)>,
         limit: Option<u32>,
         checked: Option<bool>,
-    ) -> AbstractSdkResult<Vec<(Addr, String)>> {
+    ) -> AbstractSdkResult<ActiveTasksResponse> {
         self.base.apps(self.deps).query(
             self.module_id,
             AppQueryMsg::ActiveTasks {
@@ -149,7 +451,7 @@ impl<'a, T: CronCatInterface> CronCat<'a, T> {
         start_after: Option<impl Into<String>>,
         limit: Option<u32>,
         checked: Option<bool>,
-    ) -> AbstractSdkResult<Vec<String>> {
+    ) -> AbstractSdkResult<ActiveTasksByCreatorResponse> {
         self.base.apps(self.deps).query(
             self.module_id,
             AppQueryMsg::ActiveTasksByCreator {
@@ -161,6 +463,76 @@ impl<'a, T: CronCatInterface> CronCat<'a, T> {
         )
     }
 
+    /// Active tasks by creator nested under the tag prefix
+    pub fn query_active_tasks_by_prefix(
+        &self,
+        creator_addr: impl Into<String>,
+        prefix: impl Into<String>,
+        start_after: Option<impl Into<String>>,
+        limit: Option<u32>,
+        checked: Option<bool>,
+    ) -> AbstractSdkResult<ActiveTasksByCreatorResponse> {
+        self.base.apps(self.deps).query(
+            self.module_id,
+            AppQueryMsg::ActiveTasksByPrefix {
+                creator_addr: creator_addr.into(),
+                prefix: prefix.into(),
+                start_after: start_after.map(Into::into),
+                limit,
+                checked,
+            },
+        )
+    }
+
+    /// Task tags of the creator's group
+    pub fn query_group_tasks(
+        &self,
+        creator_addr: impl Into<String>,
+        group: impl Into<String>,
+        start_after: Option<impl Into<String>>,
+        limit: Option<u32>,
+    ) -> AbstractSdkResult<GroupTasksResponse> {
+        self.base.apps(self.deps).query(
+            self.module_id,
+            AppQueryMsg::GroupTasks {
+                creator_addr: creator_addr.into(),
+                group: group.into(),
+                start_after: start_after.map(Into::into),
+                limit,
+            },
+        )
+    }
+
+    /// Creation details of the task
+    pub fn query_task_meta(
+        &self,
+        creator_addr: impl Into<String>,
+        task_tag: impl Into<String>,
+    ) -> AbstractSdkResult<Option<TaskMeta>> {
+        self.base.apps(self.deps).query(
+            self.module_id,
+            AppQueryMsg::TaskMeta {
+                creator_addr: creator_addr.into(),
+                task_tag: task_tag.into(),
+            },
+        )
+    }
+
+    /// Lifetime spend of the task
+    pub fn query_task_cost(
+        &self,
+        creator_addr: impl Into<String>,
+        task_tag: impl Into<String>,
+    ) -> AbstractSdkResult<TaskCostResponse> {
+        self.base.apps(self.deps).query(
+            self.module_id,
+            AppQueryMsg::TaskCost {
+                creator_addr: creator_addr.into(),
+                task_tag: task_tag.into(),
+            },
+        )
+    }
+
     pub fn query_manager_addr(
         &self,
         creator_addr: impl Into<String>,
@@ -174,6 +546,112 @@ impl<'a, T: CronCatInterface> CronCat<'a, T> {
             },
         )
     }
+
+    /// Next execution slots of the task
+    pub fn query_preview_schedule(
+        &self,
+        creator_addr: impl Into<String>,
+        task_tag: impl Into<String>,
+        count: Option<u32>,
+    ) -> AbstractSdkResult<PreviewScheduleResponse> {
+        self.base.apps(self.deps).query(
+            self.module_id,
+            AppQueryMsg::PreviewSchedule {
+                creator_addr: creator_addr.into(),
+                task_tag: task_tag.into(),
+                count,
+            },
+        )
+    }
+
+    /// Next execution slots of the task request, before creating it
+    pub fn query_preview_task_request_schedule(
+        &self,
+        task: CronCatTaskRequest,
+        count: Option<u32>,
+    ) -> AbstractSdkResult<PreviewScheduleResponse> {
+        self.base.apps(self.deps).query(
+            self.module_id,
+            AppQueryMsg::PreviewTaskRequestSchedule {
+                task: Box::new(task),
+                count,
+            },
+        )
+    }
+}
+
+// ANS helpers for conditional tasks
+impl<'a, T: CronCatInterface + AbstractNameService> CronCat<'a, T> {
+    /// Query of the proxy balance of the cw20 `asset`, balance is under the `"balance"` key of the response
+    pub fn proxy_cw20_balance_query(
+        &self,
+        asset: &AssetEntry,
+    ) -> AbstractSdkResult<CronCatCosmosQuery> {
+        let proxy_addr = self.base.proxy_address(self.deps)?;
+        self.cw20_balance_query(asset, &proxy_addr)
+    }
+
+    /// Query of the DEX pool balance of the cw20 `asset`, balance is under the `"balance"` key of the response.
+    /// `pool` is one of the pool references registered in ANS for the asset pairing.
+    /// The `liquidity` contract is the holder for pools with separate addresses,
+    /// pools that are only referenced by id are not supported
+    pub fn pool_cw20_balance_query(
+        &self,
+        pool: &PoolReference,
+        asset: &AssetEntry,
+    ) -> AbstractSdkResult<CronCatCosmosQuery> {
+        let pool_addr = match &pool.pool_address {
+            PoolAddress::Contract(addr) => addr,
+            PoolAddress::SeparateAddresses { liquidity, .. } => liquidity,
+            _ => {
+                return Err(AbstractSdkError::generic_err(format!(
+                    "pool {} has no contract address",
+                    pool.unique_id
+                )))
+            }
+        };
+        self.cw20_balance_query(asset, pool_addr)
+    }
+
+    fn cw20_balance_query(
+        &self,
+        asset: &AssetEntry,
+        holder: &Addr,
+    ) -> AbstractSdkResult<CronCatCosmosQuery> {
+        match self.base.name_service(self.deps).query(asset)? {
+            AssetInfo::Cw20(contract_addr) => Ok(CronCatCosmosQuery::Wasm(WasmQuery::Smart {
+                contract_addr: contract_addr.into_string(),
+                msg: to_binary(&Cw20QueryMsg::Balance {
+                    address: holder.to_string(),
+                })?,
+            })),
+            _ => Err(AbstractSdkError::generic_err(format!(
+                "{asset} is not a cw20 token"
+            ))),
+        }
+    }
+}
+
+/// Add `query` to the task and replace the value at `action_path` of the action `action_idx`
+/// with the value at `query_response_path` of the query response before each execution
+pub fn add_query_transform(
+    task: &mut CronCatTaskRequest,
+    query: CronCatCosmosQuery,
+    action_idx: u64,
+    action_path: Vec<CronCatValueIndex>,
+    query_response_path: Vec<CronCatValueIndex>,
+) {
+    let queries = task.queries.get_or_insert_with(Vec::new);
+    queries.push(query);
+    let query_idx = queries.len() as u64 - 1;
+    task.transforms
+        .get_or_insert_with(Vec::new)
+        .push(CronCatTransform {
+            action_idx,
+            query_idx,
+            action_path: CronCatPathToValue(action_path),
+            query_response_path: CronCatPathToValue(query_response_path),
+        });
 }
 
 #[cfg(test)]
@@ -184,7 +662,6 @@ mod test {
     use abstract_testing::prelude::TEST_MODULE_ID;
     use cosmwasm_std::testing::mock_dependencies;
     use cosmwasm_std::{coins, wasm_execute, BankMsg};
-    use croncat_integration_utils::*;
     use cw_asset::AssetList;
     use speculoos::prelude::*;
 
@@ -219,6 +696,8 @@ mod test {
             task: Box::new(task.clone()),
             task_tag: task_tag.clone(),
             assets: assets.clone(),
+            mode: None,
+            group: None,
         });
 
         let actual = cron_cat.create_task(task, task_tag, assets);
@@ -239,6 +718,62 @@ mod test {
         assert_that!(actual).is_equal_to(expected);
     }
 
+    #[test]
+    fn create_task_with_funds_msg() {
+        let mut deps = mock_dependencies();
+        deps.querier = abstract_testing::mock_querier();
+        let stub = MockModule::new();
+        let mut cron_cat = stub.cron_cat(deps.as_ref());
+        cron_cat.module_id = TEST_MODULE_ID;
+
+        let task = CronCatTaskRequest {
+            interval: CronCatInterval::Once,
+            boundary: None,
+            stop_on_fail: false,
+            actions: vec![CronCatAction {
+                msg: BankMsg::Send {
+                    to_address: "bob".to_owned(),
+                    amount: coins(10, "juno"),
+                }
+                .into(),
+                gas_limit: None,
+            }],
+            queries: None,
+            transforms: None,
+            cw20: None,
+        };
+        let task_tag = "bobaforbob".to_owned();
+        let expected = ExecuteMsg::from(AppExecuteMsg::CreateTask {
+            task: Box::new(task.clone()),
+            task_tag: task_tag.clone(),
+            assets: AssetListUnchecked::default(),
+            mode: None,
+            group: None,
+        });
+
+        let actual = cron_cat.create_task_with_funds(
+            task,
+            task_tag,
+            AssetListUnchecked::default(),
+            coins(10, "juno"),
+        );
+
+        assert_that!(actual).is_ok();
+
+        let actual = match actual.unwrap() {
+            CosmosMsg::Wasm(msg) => msg,
+            _ => panic!("expected wasm msg"),
+        };
+        let expected = wasm_execute(
+            abstract_testing::prelude::TEST_MODULE_ADDRESS,
+            &expected,
+            coins(10, "juno"),
+        )
+        .unwrap();
+
+        assert_that!(actual).is_equal_to(expected);
+    }
+
     #[test]
     fn refill_task_msg() {
         let mut deps = mock_dependencies();
@@ -253,6 +788,7 @@ mod test {
         let expected = ExecuteMsg::from(AppExecuteMsg::RefillTask {
             task_tag: task_tag.to_owned(),
             assets: assets.clone(),
+            creator: None,
         });
 
         let actual = cron_cat.refill_task(task_tag, assets);
@@ -285,6 +821,7 @@ mod test {
 
         let expected = ExecuteMsg::from(AppExecuteMsg::RemoveTask {
             task_tag: task_tag.to_owned(),
+            creator: None,
         });
 
         let actual = cron_cat.remove_task(task_tag);
@@ -305,6 +842,40 @@ mod test {
         assert_that!(actual).is_equal_to(expected);
     }
 
+    #[test]
+    fn adopt_task_msg() {
+        let mut deps = mock_dependencies();
+        deps.querier = abstract_testing::mock_querier();
+        let stub = MockModule::new();
+        let mut cron_cat = stub.cron_cat(deps.as_ref());
+        cron_cat.module_id = TEST_MODULE_ID;
+
+        let task_hash = TEST_TASK_HASH;
+        let task_tag = "adopted";
+
+        let expected = ExecuteMsg::from(AppExecuteMsg::AdoptTask {
+            task_hash: task_hash.to_owned(),
+            task_tag: task_tag.to_owned(),
+        });
+
+        let actual = cron_cat.adopt_task(task_hash, task_tag);
+
+        assert_that!(actual).is_ok();
+
+        let actual = match actual.unwrap() {
+            CosmosMsg::Wasm(msg) => msg,
+            _ => panic!("expected wasm msg"),
+        };
+        let expected = wasm_execute(
+            abstract_testing::prelude::TEST_MODULE_ADDRESS,
+            &expected,
+            vec![],
+        )
+        .unwrap();
+
+        assert_that!(actual).is_equal_to(expected);
+    }
+
     #[test]
     fn purge_msg() {
         let mut deps = mock_dependencies();
@@ -320,9 +891,11 @@ mod test {
 
         let expected = ExecuteMsg::from(AppExecuteMsg::Purge {
             task_tags: task_tags.clone(),
+            recover: true,
+            creator: None,
         });
 
-        let actual = cron_cat.purge(task_tags);
+        let actual = cron_cat.purge(task_tags, true);
 
         assert_that!(actual).is_ok();
 
@@ -339,4 +912,257 @@ mod test {
 
         assert_that!(actual).is_equal_to(expected);
     }
+
+    #[test]
+    fn proxy_cw20_balance_query() {
+        let mut deps = mock_dependencies();
+        let asset = AssetEntry::new("juno>token");
+        deps.querier = abstract_testing::prelude::MockQuerierBuilder::default()
+            .with_contract_map_entry(
+                "ans",
+                abstract_core::ans_host::state::ASSET_ADDRESSES,
+                (&asset, AssetInfo::cw20(Addr::unchecked("token_addr"))),
+            )
+            .build();
+        let stub = MockModule::new();
+        let cron_cat = stub.cron_cat(deps.as_ref());
+
+        let actual = cron_cat.proxy_cw20_balance_query(&asset);
+
+        let expected = CronCatCosmosQuery::Wasm(WasmQuery::Smart {
+            contract_addr: "token_addr".to_owned(),
+            msg: to_binary(&Cw20QueryMsg::Balance {
+                address: abstract_testing::prelude::TEST_PROXY.to_owned(),
+            })
+            .unwrap(),
+        });
+        assert_that!(actual).is_ok().is_equal_to(expected);
+
+        let native = AssetEntry::new("juno>juno");
+        let actual = cron_cat.proxy_cw20_balance_query(&native);
+        assert_that!(actual).is_err();
+    }
+
+    #[test]
+    fn pool_cw20_balance_query() {
+        let mut deps = mock_dependencies();
+        let asset = AssetEntry::new("juno>token");
+        deps.querier = abstract_testing::prelude::MockQuerierBuilder::default()
+            .with_contract_map_entry(
+                "ans",
+                abstract_core::ans_host::state::ASSET_ADDRESSES,
+                (&asset, AssetInfo::cw20(Addr::unchecked("token_addr"))),
+            )
+            .build();
+        let stub = MockModule::new();
+        let cron_cat = stub.cron_cat(deps.as_ref());
+        let expected = |holder: &str| {
+            CronCatCosmosQuery::Wasm(WasmQuery::Smart {
+                contract_addr: "token_addr".to_owned(),
+                msg: to_binary(&Cw20QueryMsg::Balance {
+                    address: holder.to_owned(),
+                })
+                .unwrap(),
+            })
+        };
+
+        let pool = PoolReference::new(1.into(), PoolAddress::contract(Addr::unchecked("pool")));
+        let actual = cron_cat.pool_cw20_balance_query(&pool, &asset);
+        assert_that!(actual).is_ok().is_equal_to(expected("pool"));
+
+        let pool = PoolReference::new(
+            2.into(),
+            PoolAddress::SeparateAddresses {
+                swap: Addr::unchecked("swap"),
+                liquidity: Addr::unchecked("liquidity"),
+            },
+        );
+        let actual = cron_cat.pool_cw20_balance_query(&pool, &asset);
+        assert_that!(actual)
+            .is_ok()
+            .is_equal_to(expected("liquidity"));
+
+        let pool = PoolReference::new(3.into(), PoolAddress::id(7u64));
+        let actual = cron_cat.pool_cw20_balance_query(&pool, &asset);
+        assert_that!(actual).is_err();
+    }
+
+    #[test]
+    fn query_transform() {
+        let mut task = CronCatTaskRequest {
+            interval: CronCatInterval::Once,
+            boundary: None,
+            stop_on_fail: false,
+            actions: vec![],
+            queries: Some(vec![CronCatCosmosQuery::Wasm(WasmQuery::Smart {
+                contract_addr: "first".to_owned(),
+                msg: to_binary(&Cw20QueryMsg::TokenInfo {}).unwrap(),
+            })]),
+            transforms: None,
+            cw20: None,
+        };
+        let query = CronCatCosmosQuery::Wasm(WasmQuery::Smart {
+            contract_addr: "second".to_owned(),
+            msg: to_binary(&Cw20QueryMsg::TokenInfo {}).unwrap(),
+        });
+
+        add_query_transform(
+            &mut task,
+            query.clone(),
+            0,
+            vec!["transfer".to_owned().into(), "amount".to_owned().into()],
+            vec!["balance".to_owned().into()],
+        );
+
+        assert_that!(task.queries.unwrap()[1]).is_equal_to(query);
+        assert_that!(task.transforms.unwrap()).is_equal_to(vec![CronCatTransform {
+            action_idx: 0,
+            query_idx: 1,
+            action_path: CronCatPathToValue(vec![
+                CronCatValueIndex::Key("transfer".to_owned()),
+                CronCatValueIndex::Key("amount".to_owned()),
+            ]),
+            query_response_path: CronCatPathToValue(vec![CronCatValueIndex::Key(
+                "balance".to_owned(),
+            )]),
+        }]);
+    }
+
+    #[test]
+    fn create_dca_task_msg() {
+        use abstract_core::manager::state::ACCOUNT_MODULES;
+        use abstract_testing::prelude::*;
+
+        let mut deps = mock_dependencies();
+        deps.querier = MockQuerierBuilder::default()
+            .with_contract_item(
+                TEST_PROXY,
+                cw_storage_plus::Item::new("admin"),
+                &Some(Addr::unchecked(TEST_MANAGER)),
+            )
+            .with_contract_map_entries(
+                TEST_MANAGER,
+                ACCOUNT_MODULES,
+                vec![
+                    (TEST_MODULE_ID, Addr::unchecked(TEST_MODULE_ADDRESS)),
+                    (DEX_ADAPTER_ID, Addr::unchecked("dex_addr")),
+                ],
+            )
+            .build();
+        let stub = MockModule::new();
+        let mut cron_cat = stub.cron_cat(deps.as_ref());
+        cron_cat.module_id = TEST_MODULE_ID;
+
+        let offer_asset = AssetEntry::new("juno>juno");
+        let ask_asset = AssetEntry::new("juno>atom");
+        let assets: AssetListUnchecked = AssetList::from(coins(10, "juno")).into();
+        let actual = cron_cat.create_dca_task(
+            AnsAsset::new(offer_asset.clone(), 100u128),
+            ask_asset.clone(),
+            CronCatInterval::Block(10),
+            "junoswap",
+            200_000,
+            assets.clone(),
+        );
+
+        let swap_msg = DexAdapterExecuteMsg::Module(AdapterRequestMsg {
+            proxy_address: Some(TEST_PROXY.to_owned()),
+            request: DexExecuteMsg::Action {
+                dex: "junoswap".to_owned(),
+                action: DexAction::Swap {
+                    offer_asset: AnsAsset::new(offer_asset.clone(), 100u128),
+                    ask_asset: ask_asset.clone(),
+                    max_spread: None,
+                    belief_price: None,
+                },
+            },
+        });
+        let task = CronCatTaskRequest {
+            interval: CronCatInterval::Block(10),
+            boundary: None,
+            stop_on_fail: false,
+            actions: vec![CronCatAction {
+                msg: wasm_execute("dex_addr", &swap_msg, vec![]).unwrap().into(),
+                gas_limit: Some(200_000),
+            }],
+            queries: None,
+            transforms: None,
+            cw20: None,
+        };
+        let expected = ExecuteMsg::from(AppExecuteMsg::CreateTask {
+            task: Box::new(task),
+            task_tag: "dca/junoswap/juno/juno/juno/atom".to_owned(),
+            assets,
+            mode: None,
+            group: None,
+        });
+        let expected = wasm_execute(TEST_MODULE_ADDRESS, &expected, vec![]).unwrap();
+        assert_that!(actual)
+            .is_ok()
+            .is_equal_to(CosmosMsg::Wasm(expected));
+        assert_that!(dca_task_tag(&offer_asset, &ask_asset, "junoswap"))
+            .is_equal_to("dca/junoswap/juno/juno/juno/atom".to_owned());
+    }
+
+    #[test]
+    fn active_tasks_by_prefix_query() {
+        use abstract_core::manager::state::ACCOUNT_MODULES;
+        use abstract_testing::prelude::*;
+
+        let mut deps = mock_dependencies();
+        deps.querier = MockQuerierBuilder::default()
+            .with_contract_item(
+                TEST_PROXY,
+                cw_storage_plus::Item::new("admin"),
+                &Some(Addr::unchecked(TEST_MANAGER)),
+            )
+            .with_contract_map_entry(
+                TEST_MANAGER,
+                ACCOUNT_MODULES,
+                (TEST_MODULE_ID, Addr::unchecked(TEST_MODULE_ADDRESS)),
+            )
+            .with_smart_handler(TEST_MODULE_ADDRESS, |msg| {
+                let tasks = match cosmwasm_std::from_binary(msg).unwrap() {
+                    crate::msg::QueryMsg::Module(AppQueryMsg::ActiveTasksByPrefix {
+                        prefix,
+                        ..
+                    }) => vec![format!("{prefix}/compound")],
+                    crate::msg::QueryMsg::Module(AppQueryMsg::ActiveTasksByCreator { .. }) => {
+                        vec!["tag".to_owned()]
+                    }
+                    crate::msg::QueryMsg::Module(AppQueryMsg::ActiveTasks { .. }) => {
+                        let tasks = vec![(Addr::unchecked("creator"), "tag".to_owned())];
+                        return Ok(to_binary(&ActiveTasksResponse::Unchecked { tasks }).unwrap());
+                    }
+                    _ => panic!("unexpected query"),
+                };
+                Ok(to_binary(&ActiveTasksByCreatorResponse::Unchecked { tasks }).unwrap())
+            })
+            .build();
+        let stub = MockModule::new();
+        let mut cron_cat = stub.cron_cat(deps.as_ref());
+        cron_cat.module_id = TEST_MODULE_ID;
+
+        let actual =
+            cron_cat.query_active_tasks_by_prefix("creator", "vault1", None::<String>, None, None);
+        assert_that!(actual)
+            .is_ok()
+            .is_equal_to(ActiveTasksByCreatorResponse::Unchecked {
+                tasks: vec!["vault1/compound".to_owned()],
+            });
+
+        let actual = cron_cat.query_active_tasks_by_creator("creator", None::<String>, None, None);
+        assert_that!(actual)
+            .is_ok()
+            .is_equal_to(ActiveTasksByCreatorResponse::Unchecked {
+                tasks: vec!["tag".to_owned()],
+            });
+
+        let actual = cron_cat.query_active_tasks(None, None, None);
+        assert_that!(actual)
+            .is_ok()
+            .is_equal_to(ActiveTasksResponse::Unchecked {
+                tasks: vec![(Addr::unchecked("creator"), "tag".to_owned())],
+            });
+    }
 }
@@ -8,6 +8,7 @@ use crate::{
 };
 use abstract_app::AppContract;
 use cosmwasm_std::Response;
+use cw20::Cw20ReceiveMsg;
 
 /// The version of your app
 pub const CRONCAT_MODULE_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -18,14 +19,21 @@ pub const CRONCAT_ID: &str = "croncat:app";
 pub type CroncatResult<T = Response> = Result<T, AppError>;
 
 /// The type of the app that is used to build your app and access the Abstract SDK features.
-pub type CroncatApp =
-    AppContract<AppError, AppInstantiateMsg, AppExecuteMsg, AppQueryMsg, AppMigrateMsg>;
+pub type CroncatApp = AppContract<
+    AppError,
+    AppInstantiateMsg,
+    AppExecuteMsg,
+    AppQueryMsg,
+    AppMigrateMsg,
+    Cw20ReceiveMsg,
+>;
 
 pub const CRONCAT_APP: CroncatApp = CroncatApp::new(CRONCAT_ID, CRONCAT_MODULE_VERSION, None)
     .with_instantiate(handlers::instantiate_handler)
     .with_execute(handlers::execute_handler)
     .with_query(handlers::query_handler)
     .with_migrate(handlers::migrate_handler)
+    .with_receive(handlers::receive_handler)
     .with_replies(&[
         (INSTANTIATE_REPLY_ID, replies::instantiate_reply),
         (TASK_CREATE_REPLY_ID, replies::create_task_reply),
@@ -1,7 +1,7 @@
 use abstract_app::AppError as AbstractAppError;
 use abstract_core::AbstractError;
 use abstract_sdk::AbstractSdkError;
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError};
 use croncat_integration_utils::error::CronCatContractError;
 use cw_asset::AssetError;
 use cw_controllers::AdminError;
@@ -21,6 +21,9 @@ pub enum AppError {
     #[error("{0}")]
     Asset(#[from] AssetError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     #[error("{0}")]
     Admin(#[from] AdminError),
 
@@ -35,4 +38,64 @@ pub enum AppError {
 
     #[error("Task already exists {task_tag}")]
     TaskAlreadyExists { task_tag: String },
+
+    #[error("Task {task_tag} not found on croncat")]
+    TaskNotFound { task_tag: String },
+
+    #[error("Task {task_hash} is not owned by the account")]
+    NotTaskOwner { task_hash: String },
+
+    #[error("Task {task_hash} is already registered as {task_tag}")]
+    TaskAlreadyRegistered { task_hash: String, task_tag: String },
+
+    #[error("Invalid task interval")]
+    InvalidInterval {},
+
+    #[error("Invalid task boundary for this interval")]
+    InvalidBoundary {},
+
+    #[error("Module {module_id} is not allowed to schedule tasks")]
+    CreatorNotAllowed { module_id: String },
+
+    #[error("Action {action_idx} is not allowed: {reason}")]
+    ActionNotAllowed { action_idx: usize, reason: String },
+
+    #[error("Spend limit exceeded, remaining allowance: {remaining}")]
+    SpendLimitExceeded { remaining: String },
+
+    #[error("Task budget exceeded, remaining budget: {remaining}")]
+    BudgetExceeded { remaining: String },
+
+    #[error("No funds to sponsor the task with")]
+    NoSponsorFunds {},
+
+    #[error("Pinned croncat version {version} is not available on the factory")]
+    PinnedVersionNotFound { version: String },
+
+    #[error("App is paused")]
+    Paused {},
+
+    #[error("Task group {group} not found")]
+    GroupNotFound { group: String },
+
+    #[error("Invalid group split: {reason}")]
+    InvalidGroupSplit { reason: String },
+
+    #[error("Template {name} not found")]
+    TemplateNotFound { name: String },
+
+    #[error("Template {name} already exists")]
+    TemplateAlreadyExists { name: String },
+
+    #[error("Invalid template: {reason}")]
+    InvalidTemplate { reason: String },
+
+    #[error("Invalid template params: {reason}")]
+    InvalidTemplateParams { reason: String },
+
+    #[error("Cw20 {cw20_addr} is not whitelisted on the croncat manager")]
+    Cw20NotWhitelisted { cw20_addr: String },
+
+    #[error("Invalid task tag {task_tag}: {reason}")]
+    InvalidTaskTag { task_tag: String, reason: String },
 }
@@ -1,23 +1,39 @@
-use abstract_sdk::features::{AbstractNameService, AbstractResponse, AccountIdentification};
+use std::collections::HashMap;
+
+use abstract_sdk::features::{AbstractResponse, AccountIdentification};
 use abstract_sdk::{prelude::*, AccountAction};
 use cosmwasm_std::{
-    to_binary, wasm_execute, CosmosMsg, Deps, DepsMut, Env, MessageInfo, ReplyOn, Response,
+    to_binary, wasm_execute, Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo,
+    Order, ReplyOn, Response, StdResult,
 };
-use croncat_integration_utils::task_creation::{get_croncat_contract, get_latest_croncat_contract};
-use croncat_integration_utils::{MANAGER_NAME, TASKS_NAME};
+use croncat_integration_utils::task_creation::get_croncat_contract;
+use croncat_integration_utils::{CronCatTaskExecutionInfo, AGENTS_NAME, MANAGER_NAME, TASKS_NAME};
+use croncat_sdk_factory::msg::{ContractMetadataInfo, FactoryQueryMsg};
 use croncat_sdk_manager::msg::ManagerExecuteMsg;
 use croncat_sdk_tasks::msg::{TasksExecuteMsg, TasksQueryMsg};
 use croncat_sdk_tasks::types::{TaskRequest, TaskResponse};
 use cw20::Cw20ExecuteMsg;
-use cw_asset::AssetListUnchecked;
+use cw_asset::{AssetList, AssetListUnchecked};
+use cw_storage_plus::Bound;
 
 use crate::contract::{CroncatApp, CroncatResult};
 use crate::error::AppError;
-use crate::utils::{assert_module_installed, factory_addr, sort_funds, user_balance_nonempty};
+use crate::handlers::query::DEFAULT_LIMIT;
+use crate::utils::{
+    assert_actions_allowed, assert_creator_allowed, assert_module_installed, assert_not_paused,
+    factory_addr, fill_template, new_task_croncat_contract, record_deposit, record_spending,
+    remove_active_task, sort_funds, split_assets, task_balance_assets, user_withdraw_msgs,
+    validate_action_policy, validate_spend_limit, validate_task_tag, validate_template,
+};
 
-use crate::msg::AppExecuteMsg;
+use crate::msg::{AppExecuteMsg, ConfigField, CreationMode, GroupSplit};
 use crate::replies::{TASK_CREATE_REPLY_ID, TASK_REMOVE_REPLY_ID};
-use crate::state::{Config, ACTIVE_TASKS, CONFIG, REMOVED_TASK_MANAGER_ADDR, TEMP_TASK_KEY};
+use crate::state::{
+    ActionPolicy, ActiveTask, CreatorFilter, SpendLimit, TagRules, TaskLedger, TaskMeta, Template,
+    TemplateInstance, ACTIVE_TASKS, ARCHIVED_TASKS, CONFIG, GROUP_TASKS,
+    REMOVED_TASK_MANAGER_ADDRS, SPONSORSHIPS, TASK_GROUPS, TASK_HASHES, TASK_LEDGERS, TASK_METAS,
+    TASK_TEMPLATES, TEMPLATES, TEMP_TASK_KEY,
+};
 
 pub fn execute_handler(
     deps: DepsMut,
@@ -27,61 +43,332 @@ pub fn execute_handler(
     msg: AppExecuteMsg,
 ) -> CroncatResult {
     match msg {
-        AppExecuteMsg::UpdateConfig {} => update_config(deps, info, app),
+        AppExecuteMsg::UpdateConfig {
+            factory_addr,
+            croncat_version,
+            creator_filter,
+            action_policy,
+            spend_limit,
+            pauser,
+            tag_rules,
+            unset,
+        } => update_config(
+            deps,
+            info,
+            app,
+            ConfigUpdate {
+                factory_addr,
+                croncat_version,
+                creator_filter,
+                action_policy,
+                spend_limit,
+                pauser,
+                tag_rules,
+                unset,
+            },
+        ),
+        AppExecuteMsg::Pause {} => pause(deps, info, app),
+        AppExecuteMsg::Unpause {} => unpause(deps, info, app),
         AppExecuteMsg::CreateTask {
             task,
             task_tag,
             assets,
-        } => create_task(deps, env, info, app, task, task_tag, assets),
-        AppExecuteMsg::RemoveTask { task_tag } => remove_task(deps, env, info, app, task_tag),
-        AppExecuteMsg::RefillTask { task_tag, assets } => {
-            refill_task(deps.as_ref(), env, info, app, task_tag, assets)
+            mode,
+            group,
+        } => {
+            let new_task = NewTask {
+                task_request: task,
+                task_tag,
+                assets,
+                attached: AssetList::from(info.funds),
+                mode,
+                group,
+            };
+            create_task(deps, env, info.sender, app, new_task)
+        }
+        AppExecuteMsg::CreateTaskFromTemplate {
+            template,
+            params,
+            task_tag,
+            assets,
+        } => create_task_from_template(
+            deps,
+            env,
+            info,
+            app,
+            TemplateInstance { template, params },
+            task_tag,
+            assets,
+        ),
+        AppExecuteMsg::RegisterTemplate { name, task } => {
+            register_template(deps, env, info, app, name, task)
+        }
+        AppExecuteMsg::RemoveTemplate { name } => remove_template(deps, env, info, app, name),
+        AppExecuteMsg::RemoveTask { task_tag, creator } => {
+            remove_task(deps, env, info, app, task_tag, creator)
+        }
+        AppExecuteMsg::RefillTask {
+            task_tag,
+            assets,
+            creator,
+        } => refill_task(deps, env, info, app, task_tag, assets, creator),
+        AppExecuteMsg::RemoveGroup { group } => remove_group(deps, env, info, app, group),
+        AppExecuteMsg::RefillGroup {
+            group,
+            assets,
+            split,
+        } => refill_group(deps, env, info, app, group, assets, split),
+        AppExecuteMsg::Purge {
+            task_tags,
+            recover,
+            creator,
+        } => purge(deps, env, info, app, task_tags, recover, creator),
+        AppExecuteMsg::Sponsor {
+            creator_addr,
+            task_tag,
+        } => {
+            let assets = AssetList::from(info.funds);
+            sponsor(deps, env, app, info.sender, creator_addr, task_tag, assets)
+        }
+        AppExecuteMsg::AdoptTask {
+            task_hash,
+            task_tag,
+        } => adopt_task(deps, env, info, app, task_hash, task_tag),
+        AppExecuteMsg::SetBudget { task_tag, cap } => {
+            set_budget(deps, env, info, app, task_tag, cap)
+        }
+        AppExecuteMsg::EnforceBudgets { start_after, limit } => {
+            enforce_budgets(deps, env, info, app, start_after, limit)
+        }
+        AppExecuteMsg::ForceRemoveTasks {
+            creator_addr,
+            task_tags,
+        } => force_remove_tasks(deps, env, info, app, creator_addr, task_tags),
+        AppExecuteMsg::ReclaimOrphans { start_after, limit } => {
+            reclaim_orphans(deps, env, info, app, start_after, limit)
         }
-        AppExecuteMsg::Purge { task_tags } => purge(deps, env, info, app, task_tags),
     }
 }
 
+/// Fields of the [`AppExecuteMsg::UpdateConfig`] message
+struct ConfigUpdate {
+    factory_addr: Option<String>,
+    croncat_version: Option<[u8; 2]>,
+    creator_filter: Option<CreatorFilter>,
+    action_policy: Option<ActionPolicy>,
+    spend_limit: Option<SpendLimit>,
+    pauser: Option<String>,
+    tag_rules: Option<TagRules>,
+    unset: Option<Vec<ConfigField>>,
+}
+
 /// Update the configuration of the app
-fn update_config(deps: DepsMut, msg_info: MessageInfo, app: CroncatApp) -> CroncatResult {
+fn update_config(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    app: CroncatApp,
+    update: ConfigUpdate,
+) -> CroncatResult {
+    let ConfigUpdate {
+        factory_addr,
+        croncat_version,
+        creator_filter,
+        action_policy,
+        spend_limit,
+        pauser,
+        tag_rules,
+        unset,
+    } = update;
     // Only the admin should be able to call this
     app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
 
-    CONFIG.save(deps.storage, &Config {})?;
+    let mut config = CONFIG.load(deps.storage)?;
+    for field in unset.unwrap_or_default() {
+        match field {
+            ConfigField::FactoryAddr => config.factory_addr = None,
+            ConfigField::CroncatVersion => config.croncat_version = None,
+            ConfigField::CreatorFilter => config.creator_filter = None,
+            ConfigField::ActionPolicy => config.action_policy = None,
+            ConfigField::SpendLimit => config.spend_limit = None,
+            ConfigField::Pauser => config.pauser = None,
+            ConfigField::TagRules => config.tag_rules = None,
+        }
+    }
+
+    if let Some(addr) = factory_addr {
+        config.factory_addr = Some(deps.api.addr_validate(&addr)?);
+    }
+    if let Some(version) = croncat_version {
+        config.croncat_version = Some(version);
+    }
+    if let Some(filter) = creator_filter {
+        config.creator_filter = Some(filter);
+    }
+    if let Some(policy) = action_policy {
+        config.action_policy = Some(validate_action_policy(deps.api, policy)?);
+    }
+    if let Some(limit) = spend_limit {
+        config.spend_limit = Some(validate_spend_limit(deps.api, limit)?);
+    }
+    if let Some(addr) = pauser {
+        config.pauser = Some(deps.api.addr_validate(&addr)?);
+    }
+    if let Some(rules) = tag_rules {
+        config.tag_rules = Some(rules);
+    }
+    CONFIG.save(deps.storage, &config)?;
     Ok(app.tag_response(Response::default(), "update_config"))
 }
 
+/// Pause task creation and refills
+fn pause(deps: DepsMut, msg_info: MessageInfo, app: CroncatApp) -> CroncatResult {
+    let mut config = CONFIG.load(deps.storage)?;
+    // Admin or pauser should be able to call this
+    if config.pauser.as_ref() != Some(&msg_info.sender) {
+        app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    }
+
+    config.paused = true;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(app.tag_response(Response::default(), "pause"))
+}
+
+/// Resume task creation and refills
+fn unpause(deps: DepsMut, msg_info: MessageInfo, app: CroncatApp) -> CroncatResult {
+    // Only the admin should be able to call this
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.paused = false;
+        Ok(config)
+    })?;
+    Ok(app.tag_response(Response::default(), "unpause"))
+}
+
+/// Task to create, `attached` assets are held by the app and get forwarded to the proxy
+pub(crate) struct NewTask {
+    pub task_request: Box<TaskRequest>,
+    pub task_tag: String,
+    pub assets: AssetListUnchecked,
+    pub attached: AssetList,
+    pub mode: Option<CreationMode>,
+    pub group: Option<String>,
+}
+
 /// Create a task
-fn create_task(
-    deps: DepsMut,
-    _env: Env,
-    msg_info: MessageInfo,
+pub(crate) fn create_task(
+    mut deps: DepsMut,
+    env: Env,
+    sender: Addr,
     app: CroncatApp,
-    task_request: Box<TaskRequest>,
-    task_tag: String,
-    assets: AssetListUnchecked,
+    new_task: NewTask,
 ) -> CroncatResult {
-    if app
-        .admin
-        .assert_admin(deps.as_ref(), &msg_info.sender)
-        .is_err()
-    {
-        assert_module_installed(deps.as_ref(), &msg_info.sender, &app)?;
-    }
-    let key = (msg_info.sender, task_tag);
-    if ACTIVE_TASKS.has(deps.storage, key.clone()) {
-        return Err(AppError::TaskAlreadyExists { task_tag: key.1 });
+    let NewTask {
+        task_request,
+        task_tag,
+        assets,
+        attached,
+        mode,
+        group,
+    } = new_task;
+    let is_admin = app.admin.is_admin(deps.as_ref(), &sender)?;
+    let creator_module = if is_admin {
+        None
+    } else {
+        let module_id = assert_module_installed(deps.as_ref(), &sender, &app)?;
+        assert_creator_allowed(deps.as_ref(), module_id.clone())?;
+        Some(module_id)
+    };
+    validate_task_tag(deps.as_ref(), &task_tag)?;
+    let key = (sender, task_tag);
+
+    let existing_task = ACTIVE_TASKS.may_load(deps.storage, key.clone())?;
+    let replace = match (existing_task, mode.unwrap_or_default()) {
+        (None, _) => false,
+        (Some(_), CreationMode::Fail) => {
+            return Err(AppError::TaskAlreadyExists { task_tag: key.1 })
+        }
+        (Some(task), CreationMode::ReturnExisting) => {
+            match existing_task_execution_info(deps.as_ref(), &env, &app, &key, task)? {
+                Some(execution_info) => {
+                    // Attached funds are not needed, return them to the sender
+                    let refund_msgs = attached
+                        .to_vec()
+                        .iter()
+                        .map(|asset| asset.transfer_msg(&key.0))
+                        .collect::<Result<Vec<CosmosMsg>, _>>()?;
+                    let response = Response::new()
+                        .add_messages(refund_msgs)
+                        .add_attribute("task_hash", &execution_info.task_hash)
+                        .set_data(to_binary(&execution_info)?);
+                    return Ok(app.tag_response(response, "create_task"));
+                }
+                // Task already ended on croncat, clear it like on replace and create a new one
+                None => true,
+            }
+        }
+        (Some(_), CreationMode::Replace) => true,
+    };
+
+    assert_not_paused(deps.as_ref())?;
+    let mut assets = assets.check(deps.api, None)?;
+    // Spend limit only covers funds of the proxy
+    if !is_admin {
+        record_spending(deps.storage, &env.block, &key.0, &assets)?;
     }
 
-    let (funds, cw20s) = sort_funds(deps.api, assets)?;
+    // Existing task gets removed and its balance withdrawn before the new one is created
+    let response = if replace {
+        remove_tasks(deps.branch(), &app, vec![key.clone()])?
+    } else {
+        Response::default()
+    };
+
+    // Attached funds are forwarded to the proxy before it creates the task
+    let proxy_addr = app.proxy_address(deps.as_ref())?;
+    let forward_msgs = attached
+        .to_vec()
+        .iter()
+        .map(|asset| asset.transfer_msg(&proxy_addr))
+        .collect::<Result<Vec<CosmosMsg>, _>>()?;
+    assets.add_many(&attached)?;
 
-    let factory_addr = factory_addr(&deps.querier, &app.ans_host(deps.as_ref())?)?;
+    let initial_deposit = assets.clone();
+    let (funds, cw20s) = sort_funds(assets);
+
+    let factory_addr = factory_addr(deps.as_ref(), &app)?;
     let executor = app.executor(deps.as_ref());
 
     // Getting needed croncat addresses from factory
-    let tasks_addr =
-        get_latest_croncat_contract(&deps.querier, factory_addr.clone(), TASKS_NAME.to_owned())?;
+    let tasks_addr = new_task_croncat_contract(deps.as_ref(), factory_addr.clone(), TASKS_NAME)?;
     let manager_addr =
-        get_latest_croncat_contract(&deps.querier, factory_addr, MANAGER_NAME.to_owned())?;
+        new_task_croncat_contract(deps.as_ref(), factory_addr.clone(), MANAGER_NAME)?;
+
+    if let Some(policy) = CONFIG.load(deps.storage)?.action_policy {
+        let account_base = app.account_base(deps.as_ref())?;
+        let agents_addr =
+            new_task_croncat_contract(deps.as_ref(), factory_addr.clone(), AGENTS_NAME)?;
+        assert_actions_allowed(
+            &policy,
+            &task_request.actions,
+            &[account_base.manager, account_base.proxy],
+            &[
+                factory_addr,
+                tasks_addr.clone(),
+                manager_addr.clone(),
+                agents_addr,
+            ],
+        )?;
+    }
+
+    let task_meta = TaskMeta {
+        created_at_height: env.block.height,
+        created_at: env.block.time,
+        creator_module,
+        initial_deposit: initial_deposit.clone(),
+        tasks_addr: tasks_addr.clone(),
+        manager_addr: manager_addr.clone(),
+    };
 
     // Making create task message that will be sended by the proxy
     let create_task_msg: CosmosMsg = wasm_execute(
@@ -112,13 +399,151 @@ fn create_task(
         messages.push(executor.execute(vec![cw20_transfer.into()])?);
     }
 
+    TASK_METAS.save(deps.storage, key.clone(), &task_meta)?;
+    TASK_LEDGERS.save(
+        deps.storage,
+        key.clone(),
+        &TaskLedger {
+            deposited: initial_deposit,
+            withdrawn: AssetList::new(),
+            cap: None,
+        },
+    )?;
+    if let Some(group) = group {
+        GROUP_TASKS.save(
+            deps.storage,
+            (key.0.clone(), group.clone(), key.1.clone()),
+            &Empty {},
+        )?;
+        TASK_GROUPS.save(deps.storage, key.clone(), &group)?;
+    }
     TEMP_TASK_KEY.save(deps.storage, &key)?;
-    let response = Response::default()
+    let response = response
+        .add_messages(forward_msgs)
         .add_messages(messages)
         .add_submessage(create_task_submessage);
     Ok(app.tag_response(response, "create_task"))
 }
 
+// Execution info of the existing task, same as returned by croncat on task creation
+fn existing_task_execution_info(
+    deps: Deps,
+    env: &Env,
+    app: &CroncatApp,
+    key: &(Addr, String),
+    task: ActiveTask,
+) -> CroncatResult<Option<CronCatTaskExecutionInfo>> {
+    let tasks_addr = get_croncat_contract(
+        &deps.querier,
+        factory_addr(deps, app)?,
+        TASKS_NAME.to_owned(),
+        task.task_version,
+    )?;
+    let task_response: TaskResponse = deps.querier.query_wasm_smart(
+        tasks_addr,
+        &TasksQueryMsg::Task {
+            task_hash: task.task_hash,
+        },
+    )?;
+    let Some(task_info) = task_response.task else {
+        return Ok(None);
+    };
+    // Adopted tasks have no creation metadata
+    let block_height = TASK_METAS
+        .may_load(deps.storage, key.clone())?
+        .map_or(env.block.height, |meta| meta.created_at_height);
+    Ok(Some(CronCatTaskExecutionInfo {
+        block_height,
+        tx_info: None,
+        task_hash: task_info.task_hash,
+        owner_addr: task_info.owner_addr,
+        amount_for_one_task: task_info.amount_for_one_task,
+        version: task_info.version,
+    }))
+}
+
+/// Create a task from the registered template
+fn create_task_from_template(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    app: CroncatApp,
+    instance: TemplateInstance,
+    task_tag: String,
+    assets: AssetListUnchecked,
+) -> CroncatResult {
+    let Template { task, .. } = TEMPLATES
+        .may_load(deps.storage, instance.template.clone())?
+        .ok_or_else(|| AppError::TemplateNotFound {
+            name: instance.template.clone(),
+        })?;
+    let task_request = fill_template(&task, &instance.params)?;
+
+    TASK_TEMPLATES.save(
+        deps.storage,
+        (msg_info.sender.clone(), task_tag.clone()),
+        &instance,
+    )?;
+    let new_task = NewTask {
+        task_request: Box::new(task_request),
+        task_tag,
+        assets,
+        attached: AssetList::from(msg_info.funds),
+        mode: None,
+        group: None,
+    };
+    create_task(deps, env, msg_info.sender, app, new_task)
+}
+
+/// Register a task template
+fn register_template(
+    deps: DepsMut,
+    _env: Env,
+    msg_info: MessageInfo,
+    app: CroncatApp,
+    name: String,
+    task: Binary,
+) -> CroncatResult {
+    if app
+        .admin
+        .assert_admin(deps.as_ref(), &msg_info.sender)
+        .is_err()
+    {
+        assert_module_installed(deps.as_ref(), &msg_info.sender, &app)?;
+    }
+    if TEMPLATES.has(deps.storage, name.clone()) {
+        return Err(AppError::TemplateAlreadyExists { name });
+    }
+    validate_template(&task)?;
+    TEMPLATES.save(
+        deps.storage,
+        name,
+        &Template {
+            owner: msg_info.sender,
+            task,
+        },
+    )?;
+    Ok(app.tag_response(Response::new(), "register_template"))
+}
+
+/// Remove a task template, tasks created from it are kept
+fn remove_template(
+    deps: DepsMut,
+    _env: Env,
+    msg_info: MessageInfo,
+    app: CroncatApp,
+    name: String,
+) -> CroncatResult {
+    let template = TEMPLATES
+        .may_load(deps.storage, name.clone())?
+        .ok_or_else(|| AppError::TemplateNotFound { name: name.clone() })?;
+    if template.owner != msg_info.sender {
+        app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    }
+    TEMPLATES.remove(deps.storage, name);
+    Ok(app.tag_response(Response::new(), "remove_template"))
+}
+
 /// Remove a task
 fn remove_task(
     deps: DepsMut,
@@ -126,7 +551,9 @@ fn remove_task(
     msg_info: MessageInfo,
     app: CroncatApp,
     task_tag: String,
+    creator: Option<String>,
 ) -> CroncatResult {
+    let creator = task_creator(deps.as_ref(), &app, msg_info.sender.clone(), creator)?;
     if app
         .admin
         .assert_admin(deps.as_ref(), &msg_info.sender)
@@ -134,91 +561,509 @@ fn remove_task(
     {
         assert_module_installed(deps.as_ref(), &msg_info.sender, &app)?;
     }
+    let key = (creator, task_tag);
+    let response = remove_tasks(deps, &app, vec![key])?;
+
+    Ok(app.tag_response(response, "remove_task"))
+}
+
+// Creator of the tasks, only the admin can act on behalf of other creators
+fn task_creator(
+    deps: Deps,
+    app: &CroncatApp,
+    sender: Addr,
+    creator: Option<String>,
+) -> CroncatResult<Addr> {
+    match creator {
+        Some(creator) => {
+            app.admin.assert_admin(deps, &sender)?;
+            Ok(deps.api.addr_validate(&creator)?)
+        }
+        None => Ok(sender),
+    }
+}
+
+/// Remove all tasks of the group
+fn remove_group(
+    deps: DepsMut,
+    _env: Env,
+    msg_info: MessageInfo,
+    app: CroncatApp,
+    group: String,
+) -> CroncatResult {
+    if app
+        .admin
+        .assert_admin(deps.as_ref(), &msg_info.sender)
+        .is_err()
+    {
+        assert_module_installed(deps.as_ref(), &msg_info.sender, &app)?;
+    }
+    let keys: Vec<(Addr, String)> = group_task_tags(deps.as_ref(), &msg_info.sender, &group)?
+        .into_iter()
+        .map(|tag| (msg_info.sender.clone(), tag))
+        .collect();
+    let response = remove_tasks(deps, &app, keys)?;
+
+    Ok(app.tag_response(response, "remove_group"))
+}
+
+// Tags of the creator's group, errors if the group is empty
+fn group_task_tags(deps: Deps, creator: &Addr, group: &str) -> CroncatResult<Vec<String>> {
+    let task_tags = GROUP_TASKS
+        .prefix((creator.clone(), group.to_owned()))
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<String>>>()?;
+    if task_tags.is_empty() {
+        return Err(AppError::GroupNotFound {
+            group: group.to_owned(),
+        });
+    }
+    Ok(task_tags)
+}
+
+/// Register existing croncat task of the proxy
+fn adopt_task(
+    deps: DepsMut,
+    _env: Env,
+    msg_info: MessageInfo,
+    app: CroncatApp,
+    task_hash: String,
+    task_tag: String,
+) -> CroncatResult {
+    if app
+        .admin
+        .assert_admin(deps.as_ref(), &msg_info.sender)
+        .is_err()
+    {
+        let module_id = assert_module_installed(deps.as_ref(), &msg_info.sender, &app)?;
+        assert_creator_allowed(deps.as_ref(), module_id)?;
+    }
+    validate_task_tag(deps.as_ref(), &task_tag)?;
     let key = (msg_info.sender, task_tag);
-    let (task_hash, task_version) = ACTIVE_TASKS.load(deps.storage, key.clone())?;
+    if ACTIVE_TASKS.has(deps.storage, key.clone()) {
+        return Err(AppError::TaskAlreadyExists { task_tag: key.1 });
+    }
+    if let Some((_, task_tag)) = TASK_HASHES.may_load(deps.storage, &task_hash)? {
+        return Err(AppError::TaskAlreadyRegistered {
+            task_hash,
+            task_tag,
+        });
+    }
 
-    let factory_addr = factory_addr(&deps.querier, &app.ans_host(deps.as_ref())?)?;
-    let tasks_addr = get_croncat_contract(
-        &deps.querier,
-        factory_addr.clone(),
-        TASKS_NAME.to_owned(),
-        task_version.clone(),
-    )?;
-    let manager_addr = get_croncat_contract(
-        &deps.querier,
+    // Search for the task starting from the latest croncat version
+    let factory_addr = factory_addr(deps.as_ref(), &app)?;
+    let tasks_versions: Vec<ContractMetadataInfo> = deps.querier.query_wasm_smart(
         factory_addr,
-        MANAGER_NAME.to_owned(),
-        task_version,
+        &FactoryQueryMsg::VersionsByContractName {
+            contract_name: TASKS_NAME.to_owned(),
+            from_index: None,
+            limit: None,
+        },
     )?;
+    let mut task = None;
+    for tasks_version in tasks_versions.into_iter().rev() {
+        let task_response: TaskResponse = deps.querier.query_wasm_smart(
+            tasks_version.contract_addr,
+            &TasksQueryMsg::Task {
+                task_hash: task_hash.clone(),
+            },
+        )?;
+        if task_response.task.is_some() {
+            task = task_response.task;
+            break;
+        }
+    }
+    let task = task.ok_or(AppError::TaskNotFound {
+        task_tag: key.1.clone(),
+    })?;
+    if task.owner_addr != app.proxy_address(deps.as_ref())? {
+        return Err(AppError::NotTaskOwner { task_hash });
+    }
 
-    ACTIVE_TASKS.remove(deps.storage, key);
-    let task_response: TaskResponse = deps.querier.query_wasm_smart(
-        tasks_addr.to_string(),
-        &TasksQueryMsg::Task {
-            task_hash: task_hash.to_owned(),
+    TASK_HASHES.save(deps.storage, &task.task_hash, &key)?;
+    ACTIVE_TASKS.save(
+        deps.storage,
+        key.clone(),
+        &ActiveTask {
+            task_hash: task.task_hash.clone(),
+            task_version: task.version,
         },
     )?;
+    // Deposits made before the adoption are unknown
+    TASK_LEDGERS.save(deps.storage, key, &TaskLedger::default())?;
+    Ok(app.tag_response(
+        Response::new().add_attribute("task_hash", task.task_hash),
+        "adopt_task",
+    ))
+}
 
-    // If there is still task by this hash on contract send remove message
-    // If not - check if there is anything to withdraw and withdraw if needed
-    let response = if task_response.task.is_some() {
-        let remove_task_msg: CosmosMsg = wasm_execute(
-            tasks_addr,
-            &TasksExecuteMsg::RemoveTask { task_hash },
-            vec![],
-        )?
-        .into();
+/// Remove tasks of any creator
+fn force_remove_tasks(
+    deps: DepsMut,
+    _env: Env,
+    msg_info: MessageInfo,
+    app: CroncatApp,
+    creator_addr: String,
+    task_tags: Vec<String>,
+) -> CroncatResult {
+    // Only the admin should be able to call this
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let creator_addr = deps.api.addr_validate(&creator_addr)?;
+    let keys = task_tags
+        .into_iter()
+        .map(|tag| (creator_addr.clone(), tag))
+        .collect();
+    let response = remove_tasks(deps, &app, keys)?;
+
+    Ok(app.tag_response(response, "force_remove_tasks"))
+}
+
+/// Set lifetime deposit cap of the task
+fn set_budget(
+    deps: DepsMut,
+    _env: Env,
+    msg_info: MessageInfo,
+    app: CroncatApp,
+    task_tag: String,
+    cap: Option<AssetListUnchecked>,
+) -> CroncatResult {
+    if app
+        .admin
+        .assert_admin(deps.as_ref(), &msg_info.sender)
+        .is_err()
+    {
+        assert_module_installed(deps.as_ref(), &msg_info.sender, &app)?;
+    }
+    let key = (msg_info.sender, task_tag);
+    if !ACTIVE_TASKS.has(deps.storage, key.clone()) {
+        return Err(AppError::TaskNotFound { task_tag: key.1 });
+    }
+    let cap = cap.map(|cap| cap.check(deps.api, None)).transpose()?;
+
+    let mut ledger = TASK_LEDGERS
+        .may_load(deps.storage, key.clone())?
+        .unwrap_or_default();
+    ledger.cap = cap;
+    TASK_LEDGERS.save(deps.storage, key, &ledger)?;
+    Ok(app.tag_response(Response::new(), "set_budget"))
+}
+
+/// Remove tasks that went over their budget cap
+fn enforce_budgets(
+    deps: DepsMut,
+    _env: Env,
+    _msg_info: MessageInfo,
+    app: CroncatApp,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> CroncatResult {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+    let start_after = match start_after {
+        Some((addr, tag)) => Some((deps.api.addr_validate(&addr)?, tag)),
+        None => None,
+    };
+    let factory_addr = factory_addr(deps.as_ref(), &app)?;
+
+    let mut over_budget_keys = vec![];
+    for entry in ACTIVE_TASKS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+    {
+        let (key, task) = entry?;
+        let Some(ledger) = TASK_LEDGERS.may_load(deps.storage, key.clone())? else {
+            continue;
+        };
+        if ledger.cap.is_none() {
+            continue;
+        }
+        let manager_addr = get_croncat_contract(
+            &deps.querier,
+            factory_addr.clone(),
+            MANAGER_NAME.to_owned(),
+            task.task_version,
+        )?;
+        let balance = task_balance_assets(deps.as_ref(), &manager_addr, task.task_hash)?;
+        if ledger.over_cap(balance.as_ref()).is_some() {
+            over_budget_keys.push(key);
+        }
+    }
+
+    let removed = over_budget_keys.len();
+    let response = remove_tasks(deps, &app, over_budget_keys)?
+        .add_attribute("removed_tasks", removed.to_string());
+    Ok(app.tag_response(response, "enforce_budgets"))
+}
+
+/// Remove tasks of uninstalled modules, archiving them
+fn reclaim_orphans(
+    deps: DepsMut,
+    _env: Env,
+    msg_info: MessageInfo,
+    app: CroncatApp,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> CroncatResult {
+    // Only the admin should be able to call this
+    app.admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+    let start_after = match start_after {
+        Some((addr, tag)) => Some((deps.api.addr_validate(&addr)?, tag)),
+        None => None,
+    };
+
+    let mut orphan_keys = vec![];
+    let mut installed_creators: HashMap<Addr, bool> = HashMap::new();
+    for entry in ACTIVE_TASKS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+    {
+        let (key, task) = entry?;
+        let installed = match installed_creators.get(&key.0) {
+            Some(installed) => *installed,
+            None => {
+                let installed = app.admin.is_admin(deps.as_ref(), &key.0)?
+                    || assert_module_installed(deps.as_ref(), &key.0, &app).is_ok();
+                installed_creators.insert(key.0.clone(), installed);
+                installed
+            }
+        };
+        if !installed {
+            orphan_keys.push((key, task));
+        }
+    }
+
+    for (key, task) in &orphan_keys {
+        ARCHIVED_TASKS.save(deps.storage, key.clone(), task)?;
+    }
+    let reclaimed = orphan_keys.len();
+    let response = remove_tasks(
+        deps,
+        &app,
+        orphan_keys.into_iter().map(|(key, _)| key).collect(),
+    )?
+    .add_attribute("reclaimed_tasks", reclaimed.to_string());
+
+    Ok(app.tag_response(response, "reclaim_orphans"))
+}
+
+/// Remove tasks from the state and croncat, withdrawing locked balances to the proxy
+fn remove_tasks(deps: DepsMut, app: &CroncatApp, keys: Vec<(Addr, String)>) -> CroncatResult {
+    let factory_addr = factory_addr(deps.as_ref(), app)?;
+
+    let mut remove_task_msgs: Vec<AccountAction> = vec![];
+    let mut manager_addrs: Vec<Addr> = vec![];
+    for key in keys {
+        let ActiveTask {
+            task_hash,
+            task_version,
+        } = ACTIVE_TASKS.load(deps.storage, key.clone())?;
+        let tasks_addr = get_croncat_contract(
+            &deps.querier,
+            factory_addr.clone(),
+            TASKS_NAME.to_owned(),
+            task_version.clone(),
+        )?;
+        let manager_addr = get_croncat_contract(
+            &deps.querier,
+            factory_addr.clone(),
+            MANAGER_NAME.to_owned(),
+            task_version,
+        )?;
+
+        remove_active_task(deps.storage, key.clone())?;
+        let task_response: TaskResponse = deps.querier.query_wasm_smart(
+            tasks_addr.to_string(),
+            &TasksQueryMsg::Task {
+                task_hash: task_hash.to_owned(),
+            },
+        )?;
+
+        // If there is still task by this hash on contract send remove message
+        if task_response.task.is_some() {
+            if let Some(balance) =
+                task_balance_assets(deps.as_ref(), &manager_addr, task_hash.clone())?
+            {
+                let mut ledger = TASK_LEDGERS
+                    .may_load(deps.storage, key.clone())?
+                    .unwrap_or_default();
+                ledger.withdrawn.add_many(&balance)?;
+                TASK_LEDGERS.save(deps.storage, key.clone(), &ledger)?;
+            }
+            let remove_task_msg: CosmosMsg = wasm_execute(
+                tasks_addr,
+                &TasksExecuteMsg::RemoveTask { task_hash },
+                vec![],
+            )?
+            .into();
+            remove_task_msgs.push(remove_task_msg.into());
+        }
+        if !manager_addrs.contains(&manager_addr) {
+            manager_addrs.push(manager_addr);
+        }
+    }
+
+    // Withdraw after removal in reply, or right away if there is nothing to remove
+    let response = if !remove_task_msgs.is_empty() {
         let executor_submessage = app.executor(deps.as_ref()).execute_with_reply(
-            vec![remove_task_msg.into()],
+            remove_task_msgs,
             ReplyOn::Success,
             TASK_REMOVE_REPLY_ID,
         )?;
-        REMOVED_TASK_MANAGER_ADDR.save(deps.storage, &manager_addr)?;
+        REMOVED_TASK_MANAGER_ADDRS.save(deps.storage, &manager_addrs)?;
         Response::new().add_submessage(executor_submessage)
-    } else if user_balance_nonempty(
-        deps.as_ref(),
-        app.proxy_address(deps.as_ref())?,
-        manager_addr.clone(),
-    )? {
-        // withdraw locked balance
-        let withdraw_msg: CosmosMsg = wasm_execute(
-            manager_addr,
-            &ManagerExecuteMsg::UserWithdraw { limit: None },
-            vec![],
-        )?
-        .into();
-        let executor_message = app
-            .executor(deps.as_ref())
-            .execute(vec![withdraw_msg.into()])?;
-        Response::new().add_message(executor_message)
     } else {
-        Response::new()
+        let withdraw_msgs = user_withdraw_msgs(
+            deps.as_ref(),
+            &app.proxy_address(deps.as_ref())?,
+            manager_addrs,
+        )?;
+        if withdraw_msgs.is_empty() {
+            Response::new()
+        } else {
+            let executor_message = app
+                .executor(deps.as_ref())
+                .execute(withdraw_msgs.into_iter().map(Into::into).collect())?;
+            Response::new().add_message(executor_message)
+        }
     };
-
-    Ok(app.tag_response(response, "remove_task"))
+    Ok(response)
 }
 
 /// Refill a task
 fn refill_task(
-    deps: Deps,
-    _env: Env,
+    deps: DepsMut,
+    env: Env,
     msg_info: MessageInfo,
     app: CroncatApp,
     task_tag: String,
     assets: AssetListUnchecked,
+    creator: Option<String>,
+) -> CroncatResult {
+    assert_not_paused(deps.as_ref())?;
+    let assets = assets.check(deps.api, None)?;
+    let creator = task_creator(deps.as_ref(), &app, msg_info.sender.clone(), creator)?;
+    if app
+        .admin
+        .assert_admin(deps.as_ref(), &msg_info.sender)
+        .is_err()
+    {
+        let module_id = assert_module_installed(deps.as_ref(), &msg_info.sender, &app)?;
+        assert_creator_allowed(deps.as_ref(), module_id)?;
+        record_spending(deps.storage, &env.block, &msg_info.sender, &assets)?;
+    }
+    let key = (creator, task_tag);
+    record_deposit(deps.storage, key.clone(), &assets)?;
+    let deps = deps.as_ref();
+
+    let factory_addr = factory_addr(deps, &app)?;
+    let account_action = refill_action(deps, factory_addr, key, assets)?;
+    let executor_message = app.executor(deps).execute(vec![account_action])?;
+
+    Ok(app.tag_response(Response::new().add_message(executor_message), "refill_task"))
+}
+
+/// Refill all tasks of the group
+fn refill_group(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    app: CroncatApp,
+    group: String,
+    assets: AssetListUnchecked,
+    split: GroupSplit,
 ) -> CroncatResult {
-    if app.admin.assert_admin(deps, &msg_info.sender).is_err() {
-        assert_module_installed(deps, &msg_info.sender, &app)?;
+    assert_not_paused(deps.as_ref())?;
+    let assets = assets.check(deps.api, None)?;
+    let is_admin = app.admin.is_admin(deps.as_ref(), &msg_info.sender)?;
+    if !is_admin {
+        let module_id = assert_module_installed(deps.as_ref(), &msg_info.sender, &app)?;
+        assert_creator_allowed(deps.as_ref(), module_id)?;
     }
 
-    let key = (msg_info.sender, task_tag);
-    let (task_hash, task_version) = ACTIVE_TASKS.load(deps.storage, key)?;
+    let task_tags = group_task_tags(deps.as_ref(), &msg_info.sender, &group)?;
+    let (task_tags, weights): (Vec<String>, Vec<u64>) = match split {
+        GroupSplit::Even => {
+            let weights = vec![1; task_tags.len()];
+            (task_tags, weights)
+        }
+        GroupSplit::Weighted(weights) => {
+            if let Some((tag, _)) = weights.iter().find(|(tag, _)| !task_tags.contains(tag)) {
+                return Err(AppError::InvalidGroupSplit {
+                    reason: format!("{tag} is not in the group {group}"),
+                });
+            }
+            weights.into_iter().unzip()
+        }
+    };
+    if weights.iter().sum::<u64>() == 0 {
+        return Err(AppError::InvalidGroupSplit {
+            reason: "total weight is zero".to_owned(),
+        });
+    }
+    let shares = split_assets(&assets, &weights);
 
-    let (funds, cw20s) = sort_funds(deps.api, assets)?;
+    if !is_admin {
+        let mut refilled = AssetList::new();
+        for share in &shares {
+            refilled.add_many(share)?;
+        }
+        record_spending(deps.storage, &env.block, &msg_info.sender, &refilled)?;
+    }
+    for (task_tag, share) in task_tags.iter().zip(&shares) {
+        record_deposit(
+            deps.storage,
+            (msg_info.sender.clone(), task_tag.clone()),
+            share,
+        )?;
+    }
 
-    let executor = app.executor(deps);
+    let deps = deps.as_ref();
+    let factory_addr = factory_addr(deps, &app)?;
+    let mut account_actions = vec![];
+    for (task_tag, share) in task_tags.into_iter().zip(shares) {
+        if share.is_empty() {
+            continue;
+        }
+        account_actions.push(refill_action(
+            deps,
+            factory_addr.clone(),
+            (msg_info.sender.clone(), task_tag),
+            share,
+        )?);
+    }
+    let executor_message = app.executor(deps).execute(account_actions)?;
+
+    Ok(app.tag_response(
+        Response::new().add_message(executor_message),
+        "refill_group",
+    ))
+}
+
+// Transfers of the assets to the task balance on croncat manager
+fn refill_action(
+    deps: Deps,
+    factory_addr: Addr,
+    key: (Addr, String),
+    assets: AssetList,
+) -> CroncatResult<AccountAction> {
+    let ActiveTask {
+        task_hash,
+        task_version,
+    } = ACTIVE_TASKS.load(deps.storage, key)?;
+
+    let (funds, cw20s) = sort_funds(assets);
 
-    let factory_addr = factory_addr(&deps.querier, &app.ans_host(deps)?)?;
     let manager_addr = get_croncat_contract(
         &deps.querier,
         factory_addr,
@@ -253,9 +1098,51 @@ fn refill_task(
         .into();
         account_action.merge(refill_task_msg.into());
     }
-    let msg = executor.execute(vec![account_action])?;
+    Ok(account_action)
+}
+
+/// Refill the task with funds of the sponsor, that are sent to the proxy first
+pub(crate) fn sponsor(
+    deps: DepsMut,
+    _env: Env,
+    app: CroncatApp,
+    sponsor_addr: Addr,
+    creator_addr: String,
+    task_tag: String,
+    assets: AssetList,
+) -> CroncatResult {
+    assert_not_paused(deps.as_ref())?;
+    if assets.is_empty() {
+        return Err(AppError::NoSponsorFunds {});
+    }
+    let creator_addr = deps.api.addr_validate(&creator_addr)?;
+    let key = (creator_addr, task_tag);
+    record_deposit(deps.storage, key.clone(), &assets)?;
 
-    Ok(app.tag_response(Response::new().add_message(msg), "refill_task"))
+    let sponsorship_key = (key.0.clone(), key.1.clone(), sponsor_addr.clone());
+    let mut sponsored = SPONSORSHIPS
+        .may_load(deps.storage, sponsorship_key.clone())?
+        .unwrap_or_default();
+    sponsored.add_many(&assets)?;
+    SPONSORSHIPS.save(deps.storage, sponsorship_key, &sponsored)?;
+
+    let deps = deps.as_ref();
+    let proxy_addr = app.proxy_address(deps)?;
+    let transfer_msgs = assets
+        .to_vec()
+        .iter()
+        .map(|asset| asset.transfer_msg(&proxy_addr))
+        .collect::<Result<Vec<CosmosMsg>, _>>()?;
+
+    let factory_addr = factory_addr(deps, &app)?;
+    let account_action = refill_action(deps, factory_addr, key, assets)?;
+    let executor_message = app.executor(deps).execute(vec![account_action])?;
+
+    let response = Response::new()
+        .add_messages(transfer_msgs)
+        .add_message(executor_message)
+        .add_attribute("sponsor", sponsor_addr);
+    Ok(app.tag_response(response, "sponsor"))
 }
 
 fn purge(
@@ -264,7 +1151,10 @@ fn purge(
     msg_info: MessageInfo,
     app: CroncatApp,
     task_tags: Vec<String>,
+    recover: bool,
+    creator: Option<String>,
 ) -> CroncatResult {
+    let creator = task_creator(deps.as_ref(), &app, msg_info.sender.clone(), creator)?;
     // In case module got unregistered or admin got changed they have no reason to purge now
     if app
         .admin
@@ -274,8 +1164,18 @@ fn purge(
         assert_module_installed(deps.as_ref(), &msg_info.sender, &app)?;
     }
 
+    if recover {
+        let keys = task_tags
+            .into_iter()
+            .map(|tag| (creator.clone(), tag))
+            .filter(|key| ACTIVE_TASKS.has(deps.storage, key.clone()))
+            .collect();
+        let response = remove_tasks(deps, &app, keys)?;
+        return Ok(app.tag_response(response, "purge"));
+    }
+
     for tag in task_tags {
-        ACTIVE_TASKS.remove(deps.storage, (msg_info.sender.clone(), tag));
+        remove_active_task(deps.storage, (creator.clone(), tag))?;
     }
     Ok(app.tag_response(Response::new(), "purge"))
 }
@@ -2,16 +2,46 @@ use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
 
 use crate::contract::{CroncatApp, CroncatResult};
 use crate::msg::AppInstantiateMsg;
-use crate::state::{Config, CONFIG};
+use crate::state::{Config, CONFIG, CURRENT_STATE_VERSION, STATE_VERSION};
+use crate::utils::{validate_action_policy, validate_spend_limit};
 
 pub fn instantiate_handler(
     deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
     _app: CroncatApp,
-    _msg: AppInstantiateMsg,
+    msg: AppInstantiateMsg,
 ) -> CroncatResult {
-    CONFIG.save(deps.storage, &Config {})?;
+    let factory_addr = msg
+        .factory_addr
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let action_policy = msg
+        .action_policy
+        .map(|policy| validate_action_policy(deps.api, policy))
+        .transpose()?;
+    let spend_limit = msg
+        .spend_limit
+        .map(|limit| validate_spend_limit(deps.api, limit))
+        .transpose()?;
+    let pauser = msg
+        .pauser
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            factory_addr,
+            croncat_version: msg.croncat_version,
+            creator_filter: msg.creator_filter,
+            action_policy,
+            spend_limit,
+            pauser,
+            paused: false,
+            tag_rules: msg.tag_rules,
+        },
+    )?;
+    STATE_VERSION.save(deps.storage, &CURRENT_STATE_VERSION)?;
 
     Ok(Response::new())
 }
@@ -1,15 +1,82 @@
 use crate::contract::{CroncatApp, CroncatResult};
 use crate::msg::AppMigrateMsg;
+use crate::state::{
+    ActiveTask, Config, ACTIVE_TASKS, CONFIG, CURRENT_STATE_VERSION, STATE_VERSION, TASK_HASHES,
+};
 use abstract_sdk::AbstractResponse;
-use cosmwasm_std::{DepsMut, Env, Response};
+use cosmwasm_std::{Addr, DepsMut, Env, Order, Response, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+
+/// State migration steps, step at index `n` migrates state from version `n` to `n + 1`
+const MIGRATIONS: [fn(&mut dyn Storage) -> StdResult<()>; CURRENT_STATE_VERSION as usize] =
+    [migrate_v0_to_v1, migrate_v1_to_v2];
 
 /// Handle the app migrate msg
 /// The top-level Abstract app does version checking and dispatches to this handler
 pub fn migrate_handler(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     app: CroncatApp,
     _msg: AppMigrateMsg,
 ) -> CroncatResult {
-    Ok(app.tag_response(Response::default(), "migrate"))
+    let from_version = STATE_VERSION.may_load(deps.storage)?.unwrap_or_default();
+    for migrate in MIGRATIONS.iter().skip(from_version as usize) {
+        migrate(deps.storage)?;
+    }
+    STATE_VERSION.save(deps.storage, &CURRENT_STATE_VERSION)?;
+
+    Ok(app.tag_response(
+        Response::default()
+            .add_attribute("from_state_version", from_version.to_string())
+            .add_attribute("to_state_version", CURRENT_STATE_VERSION.to_string()),
+        "migrate",
+    ))
+}
+
+/// 0.0.1 state: empty config and tasks stored as (`task_hash`, `task_version`) tuples
+fn migrate_v0_to_v1(storage: &mut dyn Storage) -> StdResult<()> {
+    // Config had no fields
+    #[cosmwasm_schema::cw_serde]
+    struct ConfigV0 {}
+    let config_v0: Item<ConfigV0> = Item::new("config");
+    config_v0.load(storage)?;
+    CONFIG.save(storage, &Config::default())?;
+
+    migrate_task_tuples(storage, "active_tasks")?;
+
+    // Replaced by the list of manager addresses
+    let removed_task_manager_addr: Item<Addr> = Item::new("removed_task_manager_addr");
+    removed_task_manager_addr.remove(storage);
+    Ok(())
+}
+
+/// Index active tasks by their hashes
+fn migrate_v1_to_v2(storage: &mut dyn Storage) -> StdResult<()> {
+    let entries = ACTIVE_TASKS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (key, task) in entries {
+        TASK_HASHES.save(storage, &task.task_hash, &key)?;
+    }
+    Ok(())
+}
+
+// Convert (`task_hash`, `task_version`) values of the tasks map to the `ActiveTask`
+fn migrate_task_tuples(storage: &mut dyn Storage, namespace: &str) -> StdResult<()> {
+    let tasks_v0: Map<(Addr, String), (String, String)> = Map::new(namespace);
+    let tasks: Map<(Addr, String), ActiveTask> = Map::new(namespace);
+    let entries = tasks_v0
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (key, (task_hash, task_version)) in entries {
+        tasks.save(
+            storage,
+            key,
+            &ActiveTask {
+                task_hash,
+                task_version,
+            },
+        )?;
+    }
+    Ok(())
 }
@@ -1,20 +1,34 @@
 use std::collections::HashMap;
 
 use crate::contract::{CroncatApp, CroncatResult};
-use crate::msg::{ActiveTasksByCreatorResponse, ActiveTasksResponse, AppQueryMsg, ConfigResponse};
-use crate::state::{ACTIVE_TASKS, CONFIG};
-use crate::utils::factory_addr;
-use abstract_sdk::features::AbstractNameService;
-use cosmwasm_std::{to_binary, Addr, Binary, Deps, Env, QuerierWrapper, StdResult};
+use crate::error::AppError;
+use crate::msg::{
+    ActiveTasksByCreatorResponse, ActiveTasksResponse, AppQueryMsg, ArchivedTasksResponse,
+    ConfigResponse, GroupTasksResponse, PreviewScheduleResponse, SpendingAllowanceResponse,
+    SponsorshipsResponse, TaskCostResponse,
+};
+use crate::state::{
+    ActiveTask, TaskLedger, TaskMeta, Template, TemplateInstance, ACTIVE_TASKS, ARCHIVED_TASKS,
+    CONFIG, GROUP_TASKS, SPONSORSHIPS, TAG_SEPARATOR, TASK_LEDGERS, TASK_METAS, TASK_TEMPLATES,
+    TEMPLATES,
+};
+use crate::utils::{
+    current_spending_window, factory_addr, new_task_croncat_contract, preview_schedule,
+    task_balance_assets, task_boundary,
+};
+use cosmwasm_std::{to_binary, Addr, Binary, Deps, Env, QuerierWrapper, StdError, StdResult};
 use croncat_integration_utils::task_creation::get_croncat_contract;
-use croncat_integration_utils::{MANAGER_NAME, TASKS_NAME};
+use croncat_integration_utils::{CronCatTaskRequest, MANAGER_NAME, TASKS_NAME};
 use croncat_sdk_manager::msg::ManagerQueryMsg;
 use croncat_sdk_manager::types::TaskBalanceResponse;
 use croncat_sdk_tasks::msg::TasksQueryMsg;
-use croncat_sdk_tasks::types::TaskResponse;
+use croncat_sdk_tasks::types::{Config as TasksConfig, TaskResponse};
+use cw_asset::{Asset, AssetInfo, AssetList};
 use cw_storage_plus::Bound;
 
 pub const DEFAULT_LIMIT: u32 = 50;
+pub const DEFAULT_PREVIEW_COUNT: u32 = 10;
+pub const MAX_PREVIEW_COUNT: u32 = 100;
 
 fn check_if_task_exists(
     querier: &QuerierWrapper,
@@ -47,7 +61,7 @@ fn check_if_task_exists(
 
 pub fn query_handler(
     deps: Deps,
-    _env: Env,
+    env: Env,
     app: &CroncatApp,
     msg: AppQueryMsg,
 ) -> CroncatResult<Binary> {
@@ -67,9 +81,74 @@ pub fn query_handler(
             deps,
             app,
             creator_addr,
+            start_after.map(Bound::exclusive),
+            None,
+            limit,
+            checked,
+        )?),
+        AppQueryMsg::ActiveTasksByPrefix {
+            creator_addr,
+            prefix,
             start_after,
             limit,
             checked,
+        } => {
+            // Tags nested under the prefix are between `prefix/` and the next char after separator
+            let first_tag = format!("{prefix}{TAG_SEPARATOR}");
+            let min = match start_after {
+                Some(start_after) if start_after >= first_tag => Bound::exclusive(start_after),
+                _ => Bound::inclusive(first_tag),
+            };
+            let max = Bound::exclusive(format!("{prefix}{}", (TAG_SEPARATOR as u8 + 1) as char));
+            to_binary(&query_active_tasks_by_creator(
+                deps,
+                app,
+                creator_addr,
+                Some(min),
+                Some(max),
+                limit,
+                checked,
+            )?)
+        }
+        AppQueryMsg::ArchivedTasks { start_after, limit } => {
+            to_binary(&query_archived_tasks(deps, start_after, limit)?)
+        }
+        AppQueryMsg::GroupTasks {
+            creator_addr,
+            group,
+            start_after,
+            limit,
+        } => to_binary(&query_group_tasks(
+            deps,
+            creator_addr,
+            group,
+            start_after,
+            limit,
+        )?),
+        AppQueryMsg::Template { name } => to_binary(&query_template(deps, name)?),
+        AppQueryMsg::TaskTemplate {
+            creator_addr,
+            task_tag,
+        } => to_binary(&query_task_template(deps, creator_addr, task_tag)?),
+        AppQueryMsg::TaskMeta {
+            creator_addr,
+            task_tag,
+        } => to_binary(&query_task_meta(deps, creator_addr, task_tag)?),
+        AppQueryMsg::TaskCost {
+            creator_addr,
+            task_tag,
+        } => to_binary(&query_task_cost(deps, app, creator_addr, task_tag)?),
+        AppQueryMsg::Sponsorships {
+            creator_addr,
+            task_tag,
+            start_after,
+            limit,
+        } => to_binary(&query_sponsorships(
+            deps,
+            creator_addr,
+            task_tag,
+            start_after,
+            limit,
         )?),
         AppQueryMsg::TaskInfo {
             creator_addr,
@@ -83,6 +162,24 @@ pub fn query_handler(
             creator_addr,
             task_tag,
         } => to_binary(&query_manager_addr(deps, app, creator_addr, task_tag)?),
+        AppQueryMsg::PreviewSchedule {
+            creator_addr,
+            task_tag,
+            count,
+        } => to_binary(&query_preview_schedule(
+            deps,
+            env,
+            app,
+            creator_addr,
+            task_tag,
+            count,
+        )?),
+        AppQueryMsg::PreviewTaskRequestSchedule { task, count } => to_binary(
+            &query_preview_task_request_schedule(deps, env, app, *task, count)?,
+        ),
+        AppQueryMsg::SpendingAllowance { creator_addr } => {
+            to_binary(&query_spending_allowance(deps, env, creator_addr)?)
+        }
     }
     .map_err(Into::into)
 }
@@ -115,20 +212,20 @@ fn query_active_tasks(
 
     let response = match check {
         true => {
-            let factory_addr = factory_addr(&deps.querier, &app.ans_host(deps)?)?;
+            let factory_addr = factory_addr(deps, app)?;
             let mut manager_addrs = HashMap::new();
             let mut removed_tasks = Vec::new();
 
             // filter tasks that doesn't exist on croncat contract anymore
             let tasks_result: StdResult<Vec<(Addr, String)>> = iter
                 .filter(|res| {
-                    res.as_ref().map_or(true, |(k, (task_hash, version))| {
+                    res.as_ref().map_or(true, |(k, task)| {
                         if check_if_task_exists(
                             &deps.querier,
                             &mut manager_addrs,
                             factory_addr.clone(),
-                            task_hash.clone(),
-                            version.clone(),
+                            task.task_hash.clone(),
+                            task.task_version.clone(),
                         ) {
                             true
                         } else {
@@ -160,7 +257,8 @@ fn query_active_tasks_by_creator(
     deps: Deps,
     app: &CroncatApp,
     creator: String,
-    start_after: Option<String>,
+    min: Option<Bound<String>>,
+    max: Option<Bound<String>>,
     limit: Option<u32>,
     checked: Option<bool>,
 ) -> CroncatResult<ActiveTasksByCreatorResponse> {
@@ -168,29 +266,27 @@ fn query_active_tasks_by_creator(
     let check = checked.unwrap_or(false);
     let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
 
-    let iter = ACTIVE_TASKS.prefix(addr).range(
-        deps.storage,
-        start_after.map(Bound::exclusive),
-        None,
-        cosmwasm_std::Order::Ascending,
-    );
+    let iter =
+        ACTIVE_TASKS
+            .prefix(addr)
+            .range(deps.storage, min, max, cosmwasm_std::Order::Ascending);
 
     match check {
         true => {
-            let factory_addr = factory_addr(&deps.querier, &app.ans_host(deps)?)?;
+            let factory_addr = factory_addr(deps, app)?;
             let mut manager_addrs = HashMap::new();
             let mut removed_tasks = Vec::new();
 
             // filter tasks that doesn't exist on croncat contract anymore
             let tasks_res: StdResult<Vec<String>> = iter
                 .filter(|res| {
-                    res.as_ref().map_or(true, |(k, (task_hash, version))| {
+                    res.as_ref().map_or(true, |(k, task)| {
                         if check_if_task_exists(
                             &deps.querier,
                             &mut manager_addrs,
                             factory_addr.clone(),
-                            task_hash.clone(),
-                            version.clone(),
+                            task.task_hash.clone(),
+                            task.task_version.clone(),
                         ) {
                             true
                         } else {
@@ -215,6 +311,159 @@ fn query_active_tasks_by_creator(
     }
 }
 
+fn query_group_tasks(
+    deps: Deps,
+    creator_addr: String,
+    group: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<GroupTasksResponse> {
+    let creator_addr = deps.api.addr_validate(&creator_addr)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+
+    let tasks = GROUP_TASKS
+        .prefix((creator_addr, group))
+        .keys(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            cosmwasm_std::Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    Ok(GroupTasksResponse { tasks })
+}
+
+fn query_template(deps: Deps, name: String) -> CroncatResult<Template> {
+    TEMPLATES
+        .may_load(deps.storage, name.clone())?
+        .ok_or(AppError::TemplateNotFound { name })
+}
+
+fn query_task_template(
+    deps: Deps,
+    creator_addr: String,
+    task_tag: String,
+) -> StdResult<Option<TemplateInstance>> {
+    let creator_addr = deps.api.addr_validate(&creator_addr)?;
+    TASK_TEMPLATES.may_load(deps.storage, (creator_addr, task_tag))
+}
+
+fn query_task_meta(
+    deps: Deps,
+    creator_addr: String,
+    task_tag: String,
+) -> StdResult<Option<TaskMeta>> {
+    let creator_addr = deps.api.addr_validate(&creator_addr)?;
+    TASK_METAS.may_load(deps.storage, (creator_addr, task_tag))
+}
+
+fn query_task_cost(
+    deps: Deps,
+    app: &CroncatApp,
+    creator_addr: String,
+    task_tag: String,
+) -> CroncatResult<TaskCostResponse> {
+    let creator_addr = deps.api.addr_validate(&creator_addr)?;
+    let key = (creator_addr, task_tag);
+    let TaskLedger {
+        deposited,
+        withdrawn,
+        cap,
+    } = TASK_LEDGERS.load(deps.storage, key.clone())?;
+
+    let balance = match ACTIVE_TASKS.may_load(deps.storage, key)? {
+        Some(ActiveTask {
+            task_hash,
+            task_version,
+        }) => {
+            let factory_addr = factory_addr(deps, app)?;
+            let manager_addr = get_croncat_contract(
+                &deps.querier,
+                factory_addr,
+                MANAGER_NAME.to_owned(),
+                task_version,
+            )?;
+            task_balance_assets(deps, &manager_addr, task_hash)?
+        }
+        None => None,
+    };
+
+    let amount_of = |assets: Option<&AssetList>, info: &AssetInfo| {
+        assets
+            .and_then(|assets| assets.find(info))
+            .map(|asset| asset.amount)
+            .unwrap_or_default()
+    };
+    let spent: Vec<Asset> = deposited
+        .to_vec()
+        .into_iter()
+        .map(|asset| {
+            let amount = asset
+                .amount
+                .saturating_sub(amount_of(balance.as_ref(), &asset.info))
+                .saturating_sub(amount_of(Some(&withdrawn), &asset.info));
+            Asset::new(asset.info, amount)
+        })
+        .collect();
+    Ok(TaskCostResponse {
+        deposited,
+        withdrawn,
+        cap,
+        balance,
+        spent: spent.into(),
+    })
+}
+
+fn query_archived_tasks(
+    deps: Deps,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> StdResult<ArchivedTasksResponse> {
+    let start_after = match start_after {
+        Some((addr, tag)) => Some((deps.api.addr_validate(&addr)?, tag)),
+        None => None,
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+
+    let tasks = ARCHIVED_TASKS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            cosmwasm_std::Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    Ok(ArchivedTasksResponse { tasks })
+}
+
+fn query_sponsorships(
+    deps: Deps,
+    creator_addr: String,
+    task_tag: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<SponsorshipsResponse> {
+    let creator_addr = deps.api.addr_validate(&creator_addr)?;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+
+    let sponsorships = SPONSORSHIPS
+        .prefix((creator_addr, task_tag))
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            cosmwasm_std::Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    Ok(SponsorshipsResponse { sponsorships })
+}
+
 fn query_task_info(
     deps: Deps,
     app: &CroncatApp,
@@ -222,9 +471,12 @@ fn query_task_info(
     task_tag: String,
 ) -> CroncatResult<TaskResponse> {
     let creator_addr = deps.api.addr_validate(&creator_addr)?;
-    let (task_hash, task_version) = ACTIVE_TASKS.load(deps.storage, (creator_addr, task_tag))?;
+    let ActiveTask {
+        task_hash,
+        task_version,
+    } = ACTIVE_TASKS.load(deps.storage, (creator_addr, task_tag))?;
 
-    let factory_addr = factory_addr(&deps.querier, &app.ans_host(deps)?)?;
+    let factory_addr = factory_addr(deps, app)?;
     let tasks_addr = get_croncat_contract(
         &deps.querier,
         factory_addr,
@@ -245,9 +497,12 @@ fn query_task_balance(
     task_tag: String,
 ) -> CroncatResult<TaskBalanceResponse> {
     let creator_addr = deps.api.addr_validate(&creator_addr)?;
-    let (task_hash, task_version) = ACTIVE_TASKS.load(deps.storage, (creator_addr, task_tag))?;
+    let ActiveTask {
+        task_hash,
+        task_version,
+    } = ACTIVE_TASKS.load(deps.storage, (creator_addr, task_tag))?;
 
-    let factory_addr = factory_addr(&deps.querier, &app.ans_host(deps)?)?;
+    let factory_addr = factory_addr(deps, app)?;
     let manager_addr = get_croncat_contract(
         &deps.querier,
         factory_addr,
@@ -268,9 +523,10 @@ fn query_manager_addr(
     task_tag: String,
 ) -> CroncatResult<Addr> {
     let creator_addr = deps.api.addr_validate(&creator_addr)?;
-    let (_, task_version) = ACTIVE_TASKS.load(deps.storage, (creator_addr, task_tag))?;
+    let ActiveTask { task_version, .. } =
+        ACTIVE_TASKS.load(deps.storage, (creator_addr, task_tag))?;
 
-    let factory_addr = factory_addr(&deps.querier, &app.ans_host(deps)?)?;
+    let factory_addr = factory_addr(deps, app)?;
     let manager_addr = get_croncat_contract(
         &deps.querier,
         factory_addr,
@@ -279,3 +535,114 @@ fn query_manager_addr(
     )?;
     Ok(manager_addr)
 }
+
+fn query_preview_schedule(
+    deps: Deps,
+    env: Env,
+    app: &CroncatApp,
+    creator_addr: String,
+    task_tag: String,
+    count: Option<u32>,
+) -> CroncatResult<PreviewScheduleResponse> {
+    let creator_addr = deps.api.addr_validate(&creator_addr)?;
+    let ActiveTask {
+        task_hash,
+        task_version,
+    } = ACTIVE_TASKS.load(deps.storage, (creator_addr, task_tag.clone()))?;
+    let count = count
+        .unwrap_or(DEFAULT_PREVIEW_COUNT)
+        .min(MAX_PREVIEW_COUNT) as usize;
+
+    let factory_addr = factory_addr(deps, app)?;
+    let tasks_addr = get_croncat_contract(
+        &deps.querier,
+        factory_addr,
+        TASKS_NAME.to_owned(),
+        task_version,
+    )?;
+
+    let task_response: TaskResponse = deps
+        .querier
+        .query_wasm_smart(&tasks_addr, &TasksQueryMsg::Task { task_hash })?;
+    let task = task_response
+        .task
+        .ok_or(AppError::TaskNotFound { task_tag })?;
+    let tasks_config: TasksConfig = deps
+        .querier
+        .query_wasm_smart(tasks_addr, &TasksQueryMsg::Config {})?;
+
+    preview_schedule(
+        &env,
+        &task.interval,
+        &task.boundary,
+        task.queries.is_some_and(|queries| !queries.is_empty()),
+        tasks_config.slot_granularity_time,
+        count,
+    )
+}
+
+fn query_preview_task_request_schedule(
+    deps: Deps,
+    env: Env,
+    app: &CroncatApp,
+    task: CronCatTaskRequest,
+    count: Option<u32>,
+) -> CroncatResult<PreviewScheduleResponse> {
+    let count = count
+        .unwrap_or(DEFAULT_PREVIEW_COUNT)
+        .min(MAX_PREVIEW_COUNT) as usize;
+
+    let factory_addr = factory_addr(deps, app)?;
+    let tasks_addr = new_task_croncat_contract(deps, factory_addr, TASKS_NAME)?;
+    let tasks_config: TasksConfig = deps
+        .querier
+        .query_wasm_smart(tasks_addr, &TasksQueryMsg::Config {})?;
+
+    let boundary = task_boundary(&env.block, &task.interval, task.boundary)?;
+    preview_schedule(
+        &env,
+        &task.interval,
+        &boundary,
+        task.queries.is_some_and(|queries| !queries.is_empty()),
+        tasks_config.slot_granularity_time,
+        count,
+    )
+}
+
+fn query_spending_allowance(
+    deps: Deps,
+    env: Env,
+    creator_addr: String,
+) -> StdResult<SpendingAllowanceResponse> {
+    let creator_addr = deps.api.addr_validate(&creator_addr)?;
+    let Some(limit) = CONFIG.load(deps.storage)?.spend_limit else {
+        return Ok(SpendingAllowanceResponse {
+            window_start: None,
+            spent: AssetList::new(),
+            remaining: AssetList::new(),
+        });
+    };
+
+    let window = current_spending_window(deps.storage, &env.block, &creator_addr, limit.period)?;
+    let window_start = window.deposits.first().map(|(time, _)| *time);
+    let spent = window
+        .spent()
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    let remaining: Vec<Asset> = limit
+        .assets
+        .to_vec()
+        .into_iter()
+        .map(|max| {
+            let spent_amount = spent
+                .find(&max.info)
+                .map(|spent| spent.amount)
+                .unwrap_or_default();
+            Asset::new(max.info, max.amount.saturating_sub(spent_amount))
+        })
+        .collect();
+    Ok(SpendingAllowanceResponse {
+        window_start,
+        spent,
+        remaining: remaining.into(),
+    })
+}
@@ -0,0 +1,64 @@
+use cosmwasm_std::{from_binary, DepsMut, Env, MessageInfo};
+use croncat_integration_utils::task_creation::get_croncat_contract;
+use croncat_integration_utils::MANAGER_NAME;
+use cw20::Cw20ReceiveMsg;
+use cw_asset::{Asset, AssetList};
+
+use crate::contract::{CroncatApp, CroncatResult};
+use crate::handlers::execute::{create_task, sponsor, NewTask};
+use crate::msg::AppReceiveMsg;
+use crate::state::{ActiveTask, ACTIVE_TASKS};
+use crate::utils::{assert_cw20_whitelisted, factory_addr, new_task_croncat_contract};
+
+pub fn receive_handler(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    app: CroncatApp,
+    msg: Cw20ReceiveMsg,
+) -> CroncatResult {
+    let sender = deps.api.addr_validate(&msg.sender)?;
+    let factory_addr = factory_addr(deps.as_ref(), &app)?;
+    match from_binary(&msg.msg)? {
+        AppReceiveMsg::CreateTask {
+            task,
+            task_tag,
+            assets: proxy_assets,
+            mode,
+            group,
+        } => {
+            // Sender of the receive hook is the cw20 contract, it has to be trusted
+            // as it vouches for both the creator and the amount
+            let manager_addr =
+                new_task_croncat_contract(deps.as_ref(), factory_addr, MANAGER_NAME)?;
+            assert_cw20_whitelisted(deps.as_ref(), &manager_addr, &info.sender)?;
+            let new_task = NewTask {
+                task_request: task,
+                task_tag,
+                assets: proxy_assets,
+                attached: AssetList::from(vec![Asset::cw20(info.sender, msg.amount)]),
+                mode,
+                group,
+            };
+            create_task(deps, env, sender, app, new_task)
+        }
+        AppReceiveMsg::Sponsor {
+            creator_addr,
+            task_tag,
+        } => {
+            let ActiveTask { task_version, .. } = ACTIVE_TASKS.load(
+                deps.storage,
+                (deps.api.addr_validate(&creator_addr)?, task_tag.clone()),
+            )?;
+            let manager_addr = get_croncat_contract(
+                &deps.querier,
+                factory_addr,
+                MANAGER_NAME.to_owned(),
+                task_version,
+            )?;
+            assert_cw20_whitelisted(deps.as_ref(), &manager_addr, &info.sender)?;
+            let assets = AssetList::from(vec![Asset::cw20(info.sender, msg.amount)]);
+            sponsor(deps, env, app, sender, creator_addr, task_tag, assets)
+        }
+    }
+}
@@ -13,7 +13,10 @@ pub use contract::interface::CroncatApp;
 #[cfg(feature = "interface")]
 pub use msg::{AppExecuteMsgFns, AppQueryMsgFns};
 
-pub use api::{CronCat, CronCatInterface, CRON_CAT_FACTORY};
+pub use api::{
+    add_query_transform, dca_task_request, dca_task_tag, CronCat, CronCatInterface,
+    CRON_CAT_FACTORY, DEX_ADAPTER_ID,
+};
 
 // For re-exports of other crates
 pub use croncat_integration_utils;
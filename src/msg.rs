@@ -1,40 +1,223 @@
 use cosmwasm_schema::QueryResponses;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Binary, Timestamp};
 use croncat_integration_utils::CronCatTaskRequest;
-use cw_asset::AssetListUnchecked;
+use croncat_sdk_tasks::types::SlotType;
+use cw_asset::{AssetList, AssetListUnchecked};
 
-use crate::{contract::CroncatApp, state::Config};
+use crate::{
+    contract::CroncatApp,
+    state::{ActionPolicy, ActiveTask, Config, CreatorFilter, SpendLimit, TagRules},
+};
 
 // Expose the top-level app messages
 abstract_app::app_messages!(CroncatApp, AppExecuteMsg, AppQueryMsg);
 
 /// App instantiate message
 #[cosmwasm_schema::cw_serde]
-pub struct AppInstantiateMsg {}
+pub struct AppInstantiateMsg {
+    /// Croncat factory address override, by default it's resolved from the ANS
+    pub factory_addr: Option<String>,
+    /// Croncat `[major, minor]` version for new tasks, by default it's the latest version
+    pub croncat_version: Option<[u8; 2]>,
+    /// Modules allowed or denied to schedule tasks
+    pub creator_filter: Option<CreatorFilter>,
+    /// Restrictions for actions of the created tasks
+    pub action_policy: Option<ActionPolicy>,
+    /// Deposit limits of creator modules
+    pub spend_limit: Option<SpendLimit>,
+    /// Emergency pauser address
+    pub pauser: Option<String>,
+    /// Task tag restrictions
+    pub tag_rules: Option<TagRules>,
+}
 
 /// App execute messages
 #[cosmwasm_schema::cw_serde]
 #[cfg_attr(feature = "interface", derive(cw_orch::ExecuteFns))]
 #[cfg_attr(feature = "interface", impl_into(ExecuteMsg))]
 pub enum AppExecuteMsg {
-    UpdateConfig {},
+    /// Update app configuration, fields that are not set are left unchanged
+    UpdateConfig {
+        factory_addr: Option<String>,
+        croncat_version: Option<[u8; 2]>,
+        creator_filter: Option<CreatorFilter>,
+        action_policy: Option<ActionPolicy>,
+        spend_limit: Option<SpendLimit>,
+        pauser: Option<String>,
+        tag_rules: Option<TagRules>,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "Fields to reset to their defaults, applied before the set fields"
+        )]
+        unset: Option<Vec<ConfigField>>,
+    },
+    /// Block task creation and refills, admin or pauser only
+    Pause {},
+    /// Unblock task creation and refills, admin only
+    Unpause {},
+    /// Create task, funded from the proxy.
+    /// Native funds attached to the message are forwarded to the proxy and added to `assets`
+    #[cfg_attr(feature = "interface", payable)]
     CreateTask {
         task: Box<CronCatTaskRequest>,
         task_tag: String,
         assets: AssetListUnchecked,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "What to do if task with this tag already exists, fails by default"
+        )]
+        mode: Option<CreationMode>,
+        #[cfg_attr(not(feature = "interface"), doc = "Group to add the task to")]
+        group: Option<String>,
+    },
+    /// Create task from the registered template, filling its placeholders with `params`.
+    /// Attached native funds are handled the same way as in [`AppExecuteMsg::CreateTask`]
+    #[cfg_attr(feature = "interface", payable)]
+    CreateTaskFromTemplate {
+        template: String,
+        params: Vec<(String, String)>,
+        task_tag: String,
+        assets: AssetListUnchecked,
     },
+    /// Register task template, admin or installed module only.
+    /// Template is the json of the `CronCatTaskRequest` with `{{name}}` placeholders.
+    /// Placeholders inside json strings are replaced with the param text, which covers string fields and amounts.
+    /// Placeholders outside of json strings, like `"block": {{blocks}}`, take params that are json numbers or booleans.
+    /// Wasm execute `msg` of the actions can be a json object to template it as well
+    RegisterTemplate { name: String, task: Binary },
+    /// Remove task template, admin or template owner only
+    RemoveTemplate { name: String },
     RemoveTask {
         task_tag: String,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "Creator of the task, admin only. Defaults to the caller"
+        )]
+        creator: Option<String>,
     },
     RefillTask {
         task_tag: String,
         assets: AssetListUnchecked,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "Creator of the task, admin only. Defaults to the caller"
+        )]
+        creator: Option<String>,
+    },
+    /// Remove all tasks of the caller's group
+    RemoveGroup { group: String },
+    /// Refill all tasks of the caller's group, splitting the assets between them
+    RefillGroup {
+        group: String,
+        assets: AssetListUnchecked,
+        split: GroupSplit,
     },
+    /// Remove tasks from the state, without touching croncat unless `recover` is set.
+    /// On `recover` tasks are removed from croncat and balances withdrawn to the proxy
     Purge {
         task_tags: Vec<String>,
+        #[serde(default)]
+        recover: bool,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "Creator of the tasks, admin only. Defaults to the caller"
+        )]
+        creator: Option<String>,
+    },
+    /// Refill the task with attached native funds, can be called by anyone.
+    /// Cw20 tokens are sponsored with [`AppReceiveMsg::Sponsor`] through the cw20 `Send`
+    #[cfg_attr(feature = "interface", payable)]
+    Sponsor {
+        creator_addr: String,
+        task_tag: String,
+    },
+    /// Register task created by the proxy on croncat under the caller
+    AdoptTask { task_hash: String, task_tag: String },
+    /// Set lifetime deposit cap of the caller's task, refills over the cap are refused
+    SetBudget {
+        task_tag: String,
+        cap: Option<AssetListUnchecked>,
+    },
+    /// Remove tasks that are over their cap and withdraw the balances to the proxy.
+    /// Can be called by anyone
+    EnforceBudgets {
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+    /// Remove tasks of any creator, admin only
+    ForceRemoveTasks {
+        creator_addr: String,
+        task_tags: Vec<String>,
+    },
+    /// Remove tasks of modules that are no longer installed and archive them, admin only
+    ReclaimOrphans {
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "The addr and task tag to start checking after."
+        )]
+        start_after: Option<(String, String)>,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "Maximum number of active tasks to check. Default limit is 50, if not set"
+        )]
+        limit: Option<u32>,
+    },
+}
+
+/// Optional field of the app configuration
+#[cosmwasm_schema::cw_serde]
+pub enum ConfigField {
+    FactoryAddr,
+    CroncatVersion,
+    CreatorFilter,
+    ActionPolicy,
+    SpendLimit,
+    Pauser,
+    TagRules,
+}
+
+/// App cw20 receive hook messages, only accepted from cw20s whitelisted on the croncat manager
+#[cosmwasm_schema::cw_serde]
+pub enum AppReceiveMsg {
+    /// Create task on behalf of the cw20 sender, received tokens are added to `assets`
+    CreateTask {
+        task: Box<CronCatTaskRequest>,
+        task_tag: String,
+        assets: AssetListUnchecked,
+        mode: Option<CreationMode>,
+        group: Option<String>,
+    },
+    /// Refill the task with received tokens
+    Sponsor {
+        creator_addr: String,
+        task_tag: String,
     },
 }
 
+/// Behavior of task creation when the task tag is taken
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub enum CreationMode {
+    /// Return [`crate::error::AppError::TaskAlreadyExists`] error
+    #[default]
+    Fail,
+    /// Keep the existing task, its [`croncat_integration_utils::CronCatTaskExecutionInfo`]
+    /// is returned in the response data like on creation, without `tx_info`.
+    /// If the task is not on croncat anymore it gets cleared and the new task is created
+    ReturnExisting,
+    /// Remove the existing task and create the new one
+    Replace,
+}
+
+/// How group refill assets are split between the tasks of the group
+#[cosmwasm_schema::cw_serde]
+pub enum GroupSplit {
+    /// Equal share for every task
+    Even,
+    /// Shares proportional to the weights by task tag, unlisted tasks are not refilled
+    Weighted(Vec<(String, u64)>),
+}
+
 #[cosmwasm_schema::cw_serde]
 #[cfg_attr(feature = "interface", derive(cw_orch::QueryFns))]
 #[cfg_attr(feature = "interface", impl_into(QueryMsg))]
@@ -85,6 +268,99 @@ pub enum AppQueryMsg {
         #[cfg_attr(not(feature = "interface"), doc = "Defaults to false")]
         checked: Option<bool>,
     },
+    /// Get active tasks of the creator nested under the tag prefix
+    #[returns(ActiveTasksByCreatorResponse)]
+    ActiveTasksByPrefix {
+        creator_addr: String,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "Tag prefix without the trailing separator, e.g. `vault1` lists `vault1/compound`"
+        )]
+        prefix: String,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "The task tag to start listing after."
+        )]
+        start_after: Option<String>,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "Maximum number of tasks to return. Default limit is 50, if not set"
+        )]
+        limit: Option<u32>,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "On true check if this task exist on croncat contract and filter if it doesn't."
+        )]
+        #[cfg_attr(not(feature = "interface"), doc = "Defaults to false")]
+        checked: Option<bool>,
+    },
+    /// Get archived orphan tasks
+    #[returns(ArchivedTasksResponse)]
+    ArchivedTasks {
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "The addr and task tag to start listing after."
+        )]
+        start_after: Option<(String, String)>,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "Maximum number of tasks to return. Default limit is 50, if not set"
+        )]
+        limit: Option<u32>,
+    },
+    /// Get task tags of the creator's group
+    #[returns(GroupTasksResponse)]
+    GroupTasks {
+        creator_addr: String,
+        group: String,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "The task tag to start listing after."
+        )]
+        start_after: Option<String>,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "Maximum number of tasks to return. Default limit is 50, if not set"
+        )]
+        limit: Option<u32>,
+    },
+    /// Get registered task template
+    #[returns(crate::state::Template)]
+    Template { name: String },
+    /// Get template the task was created from
+    #[returns(Option<crate::state::TemplateInstance>)]
+    TaskTemplate {
+        creator_addr: String,
+        task_tag: String,
+    },
+    /// Get creation details of the task, none for adopted tasks
+    #[returns(Option<crate::state::TaskMeta>)]
+    TaskMeta {
+        creator_addr: String,
+        task_tag: String,
+    },
+    /// Get lifetime spend of the task, by asset
+    #[returns(TaskCostResponse)]
+    TaskCost {
+        creator_addr: String,
+        task_tag: String,
+    },
+    /// Get sponsors of the task with their sponsored totals
+    #[returns(SponsorshipsResponse)]
+    Sponsorships {
+        creator_addr: String,
+        task_tag: String,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "The sponsor address to start listing after."
+        )]
+        start_after: Option<String>,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "Maximum number of sponsors to return. Default limit is 50, if not set"
+        )]
+        limit: Option<u32>,
+    },
     /// Get task info
     #[returns(croncat_sdk_tasks::types::TaskResponse)]
     TaskInfo {
@@ -104,10 +380,34 @@ pub enum AppQueryMsg {
         creator_addr: String,
         task_tag: String,
     },
+    /// Get next execution slots of task
+    #[returns(PreviewScheduleResponse)]
+    PreviewSchedule {
+        creator_addr: String,
+        task_tag: String,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "Maximum number of slots to return. Default count is 10, if not set"
+        )]
+        count: Option<u32>,
+    },
+    /// Get next execution slots of task request, before creating it
+    #[returns(PreviewScheduleResponse)]
+    PreviewTaskRequestSchedule {
+        task: Box<CronCatTaskRequest>,
+        #[cfg_attr(
+            not(feature = "interface"),
+            doc = "Maximum number of slots to return. Default count is 10, if not set"
+        )]
+        count: Option<u32>,
+    },
+    /// Get deposits of creator in the current spending window
+    #[returns(SpendingAllowanceResponse)]
+    SpendingAllowance { creator_addr: String },
 }
 
 #[cosmwasm_schema::cw_serde]
-pub enum AppMigrateMsg {}
+pub struct AppMigrateMsg {}
 
 #[cosmwasm_schema::cw_serde]
 pub enum Cw20HookMsg {
@@ -140,3 +440,51 @@ pub enum ActiveTasksByCreatorResponse {
         removed_tasks: Vec<String>,
     },
 }
+
+#[cosmwasm_schema::cw_serde]
+pub struct ArchivedTasksResponse {
+    /// (`creator_addr`, `task_tag`) of the archived tasks with their croncat tasks
+    pub tasks: Vec<((Addr, String), ActiveTask)>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct GroupTasksResponse {
+    pub tasks: Vec<String>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct SponsorshipsResponse {
+    /// Sponsors with their sponsored totals
+    pub sponsorships: Vec<(Addr, AssetList)>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct PreviewScheduleResponse {
+    /// Block slots are block heights, cron slots are timestamps in nanos
+    pub slot_type: SlotType,
+    pub slots: Vec<u64>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct TaskCostResponse {
+    pub deposited: AssetList,
+    pub withdrawn: AssetList,
+    /// Lifetime deposit cap of the task
+    pub cap: Option<AssetList>,
+    /// Current balance on croncat, none if the task is not active anymore
+    pub balance: Option<AssetList>,
+    /// Deposits that are neither in the balance nor withdrawn.
+    /// Upper bound for tasks that ended on croncat before removal: their leftovers are refunded
+    /// to the user balance of the proxy, which is not tracked per task
+    pub spent: AssetList,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct SpendingAllowanceResponse {
+    /// Time of the oldest deposit within the last period, none if there are no such deposits.
+    /// Its amounts become available again at `window_start + period`
+    pub window_start: Option<Timestamp>,
+    pub spent: AssetList,
+    /// Amounts that still can be deposited in the current window, for every limited asset
+    pub remaining: AssetList,
+}
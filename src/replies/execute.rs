@@ -1,21 +1,28 @@
 use crate::{
     contract::{CroncatApp, CroncatResult},
-    state::{ACTIVE_TASKS, REMOVED_TASK_MANAGER_ADDR, TEMP_TASK_KEY},
-    utils::user_balance_nonempty,
+    state::{ActiveTask, ACTIVE_TASKS, REMOVED_TASK_MANAGER_ADDRS, TASK_HASHES, TEMP_TASK_KEY},
+    utils::user_withdraw_msgs,
 };
 
 use abstract_sdk::{
     features::{AbstractResponse, AccountIdentification},
     Execution,
 };
-use cosmwasm_std::{wasm_execute, CosmosMsg, DepsMut, Env, Reply, Response};
+use cosmwasm_std::{DepsMut, Env, Reply, Response};
 use croncat_integration_utils::reply_handler::reply_handle_croncat_task_creation;
-use croncat_sdk_manager::msg::ManagerExecuteMsg;
 
 pub fn create_task_reply(deps: DepsMut, _env: Env, app: CroncatApp, reply: Reply) -> CroncatResult {
     let (task, bin) = reply_handle_croncat_task_creation(reply)?;
     let key = TEMP_TASK_KEY.load(deps.storage)?;
-    ACTIVE_TASKS.save(deps.storage, key, &(task.task_hash.clone(), task.version))?;
+    TASK_HASHES.save(deps.storage, &task.task_hash, &key)?;
+    ACTIVE_TASKS.save(
+        deps.storage,
+        key,
+        &ActiveTask {
+            task_hash: task.task_hash.clone(),
+            task_version: task.version,
+        },
+    )?;
 
     Ok(app.tag_response(
         Response::new()
@@ -31,25 +38,20 @@ pub fn task_remove_reply(
     app: CroncatApp,
     _reply: Reply,
 ) -> CroncatResult {
-    let manager_addr = REMOVED_TASK_MANAGER_ADDR.load(deps.storage)?;
-    let response = if user_balance_nonempty(
+    let manager_addrs = REMOVED_TASK_MANAGER_ADDRS.load(deps.storage)?;
+    // withdraw locked balances
+    let withdraw_msgs = user_withdraw_msgs(
         deps.as_ref(),
-        app.proxy_address(deps.as_ref())?,
-        manager_addr.clone(),
-    )? {
-        // withdraw locked balance
-        let withdraw_msg: CosmosMsg = wasm_execute(
-            manager_addr,
-            &ManagerExecuteMsg::UserWithdraw { limit: None },
-            vec![],
-        )?
-        .into();
+        &app.proxy_address(deps.as_ref())?,
+        manager_addrs,
+    )?;
+    let response = if withdraw_msgs.is_empty() {
+        Response::new()
+    } else {
         let executor_message = app
             .executor(deps.as_ref())
-            .execute(vec![withdraw_msg.into()])?;
+            .execute(withdraw_msgs.into_iter().map(Into::into).collect())?;
         Response::new().add_message(executor_message)
-    } else {
-        Response::new()
     };
     Ok(app.tag_response(response, "task_remove_reply"))
 }
@@ -1,13 +1,208 @@
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Binary, Coin, Empty, Timestamp};
+use cw_asset::{Asset, AssetError, AssetList};
 use cw_storage_plus::{Item, Map};
 
 #[cosmwasm_schema::cw_serde]
-pub struct Config {}
+#[derive(Default)]
+pub struct Config {
+    /// Address of the croncat factory, used instead of the ANS entry if set
+    pub factory_addr: Option<Addr>,
+    /// Croncat `[major, minor]` version for new tasks, latest version is used if not set
+    pub croncat_version: Option<[u8; 2]>,
+    /// Restricts which modules can schedule tasks, any installed module can if not set
+    pub creator_filter: Option<CreatorFilter>,
+    /// Restricts actions of created tasks, actions are not checked if not set
+    pub action_policy: Option<ActionPolicy>,
+    /// Limits deposits of every creator module per period, no limits if not set
+    pub spend_limit: Option<SpendLimit>,
+    /// Address allowed to pause the app, in addition to the admin
+    pub pauser: Option<Addr>,
+    /// Task creation and refills are blocked while paused
+    pub paused: bool,
+    /// Rules for new task tags, tags are not validated if not set
+    pub tag_rules: Option<TagRules>,
+}
+
+/// List of module ids allowed or denied to schedule tasks
+#[cosmwasm_schema::cw_serde]
+pub enum CreatorFilter {
+    Allow(Vec<String>),
+    Deny(Vec<String>),
+}
+
+impl CreatorFilter {
+    pub fn is_allowed(&self, module_id: &str) -> bool {
+        match self {
+            CreatorFilter::Allow(module_ids) => module_ids.iter().any(|id| id == module_id),
+            CreatorFilter::Deny(module_ids) => module_ids.iter().all(|id| id != module_id),
+        }
+    }
+}
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
-/// Map: (`creator_addr`, `task_tag`): (`task_hash`, `task_version`)
-pub const ACTIVE_TASKS: Map<(Addr, String), (String, String)> = Map::new("active_tasks");
+/// Separator of the task tag segments, e.g. `vault1/compound`
+pub const TAG_SEPARATOR: char = '/';
+
+/// Restrictions for task tags.
+/// Tags consist of non-empty segments separated by [`TAG_SEPARATOR`]
+#[cosmwasm_schema::cw_serde]
+pub struct TagRules {
+    /// Maximum length of the whole tag in bytes
+    pub max_len: u32,
+    /// Characters allowed in tag segments besides ascii alphanumerics
+    pub allowed_chars: String,
+}
+
+/// Restrictions for actions of the created tasks
+#[cosmwasm_schema::cw_serde]
+pub struct ActionPolicy {
+    /// Contracts that wasm actions are allowed to call, any contract if not set
+    pub allowed_contracts: Option<Vec<Addr>>,
+    /// Message types allowed to be used in actions
+    pub allowed_msg_types: Vec<ActionMsgType>,
+    /// Maximum native amount attached to a single action, by denom.
+    /// Denoms that are not listed can't be attached, no limit if not set
+    pub max_native_amounts: Option<Vec<Coin>>,
+    /// Allow actions that call the manager or proxy of the account
+    pub allow_account_calls: bool,
+    /// Allow actions that call croncat contracts
+    pub allow_croncat_calls: bool,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub enum ActionMsgType {
+    Bank,
+    Wasm,
+}
+
+/// Maximum amounts that a creator module can deposit to its tasks per period.
+/// Assets that are not listed are not limited
+#[cosmwasm_schema::cw_serde]
+pub struct SpendLimit {
+    pub assets: AssetList,
+    /// Length of the rolling spending window in seconds
+    pub period: u64,
+}
+
+/// Max number of deposits kept in the spending window,
+/// the oldest deposit is merged into the next one when it's exceeded
+pub const MAX_SPENDING_DEPOSITS: usize = 32;
+
+/// Deposits of the creator within the last spend limit period, oldest first
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct SpendingWindow {
+    pub deposits: Vec<(Timestamp, AssetList)>,
+}
+
+impl SpendingWindow {
+    /// Total of the deposits in the window
+    pub fn spent(&self) -> Result<AssetList, AssetError> {
+        let mut spent = AssetList::new();
+        for (_, assets) in &self.deposits {
+            spent.add_many(assets)?;
+        }
+        Ok(spent)
+    }
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ActiveTask {
+    pub task_hash: String,
+    pub task_version: String,
+}
+
+/// Map: (`creator_addr`, `task_tag`): active task
+pub const ACTIVE_TASKS: Map<(Addr, String), ActiveTask> = Map::new("active_tasks");
+
+/// Map: (`creator_addr`, `group`, `task_tag`): task of the group
+pub const GROUP_TASKS: Map<(Addr, String, String), Empty> = Map::new("group_tasks");
+
+/// Map: (`creator_addr`, `task_tag`): group of the task
+pub const TASK_GROUPS: Map<(Addr, String), String> = Map::new("task_groups");
+
+/// Json of the task request with `{{name}}` placeholders
+#[cosmwasm_schema::cw_serde]
+pub struct Template {
+    /// Module or admin that registered the template
+    pub owner: Addr,
+    pub task: Binary,
+}
+
+/// Template and parameters the task was created from
+#[cosmwasm_schema::cw_serde]
+pub struct TemplateInstance {
+    pub template: String,
+    pub params: Vec<(String, String)>,
+}
+
+/// Creation details of the task
+#[cosmwasm_schema::cw_serde]
+pub struct TaskMeta {
+    pub created_at_height: u64,
+    pub created_at: Timestamp,
+    /// Module id of the creator, none if created by the admin
+    pub creator_module: Option<String>,
+    pub initial_deposit: AssetList,
+    pub tasks_addr: Addr,
+    pub manager_addr: Addr,
+}
+
+/// Map: (`creator_addr`, `task_tag`): creation details, missing for adopted tasks
+pub const TASK_METAS: Map<(Addr, String), TaskMeta> = Map::new("task_metas");
+
+/// Lifetime deposits and withdrawals of the task
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct TaskLedger {
+    pub deposited: AssetList,
+    /// Task balances withdrawn to the proxy on removal.
+    /// Leftovers of tasks that already ended on croncat are not included
+    pub withdrawn: AssetList,
+    /// Maximum lifetime deposits, assets that are not listed are not capped
+    pub cap: Option<AssetList>,
+}
+
+impl TaskLedger {
+    /// Capped asset that is over the cap, checked against deposits and the current balance
+    pub fn over_cap(&self, balance: Option<&AssetList>) -> Option<Asset> {
+        let cap = self.cap.as_ref()?;
+        cap.into_iter()
+            .find(|max| {
+                let deposited = self.deposited.find(&max.info).map(|a| a.amount);
+                let balance = balance.and_then(|b| b.find(&max.info)).map(|a| a.amount);
+                deposited.unwrap_or_default() > max.amount
+                    || balance.unwrap_or_default() > max.amount
+            })
+            .cloned()
+    }
+}
+
+/// Map: (`creator_addr`, `task_tag`): funding ledger, kept after removal until the tag is reused
+pub const TASK_LEDGERS: Map<(Addr, String), TaskLedger> = Map::new("task_ledgers");
+
+/// Map: (`creator_addr`, `task_tag`, `sponsor_addr`): total sponsored assets
+pub const SPONSORSHIPS: Map<(Addr, String, Addr), AssetList> = Map::new("sponsorships");
+
+/// Map: `template_name`: template
+pub const TEMPLATES: Map<String, Template> = Map::new("templates");
+
+/// Map: (`creator_addr`, `task_tag`): template the task was created from
+pub const TASK_TEMPLATES: Map<(Addr, String), TemplateInstance> = Map::new("task_templates");
+
+/// Map: `creator_addr`: current spending window
+pub const SPENDING_WINDOWS: Map<&Addr, SpendingWindow> = Map::new("spending_windows");
+
+/// Map: `task_hash`: (`creator_addr`, `task_tag`) of the active task
+pub const TASK_HASHES: Map<&str, (Addr, String)> = Map::new("task_hashes");
+
+/// Map: (`creator_addr`, `task_tag`): reclaimed orphan task
+pub const ARCHIVED_TASKS: Map<(Addr, String), ActiveTask> = Map::new("archived_tasks");
 
 pub const TEMP_TASK_KEY: Item<(Addr, String)> = Item::new("temp_task_key");
-pub const REMOVED_TASK_MANAGER_ADDR: Item<Addr> = Item::new("removed_task_manager_addr");
+pub const REMOVED_TASK_MANAGER_ADDRS: Item<Vec<Addr>> = Item::new("removed_task_manager_addrs");
+
+/// Version of the state layout, missing for the 0.0.1 state
+pub const STATE_VERSION: Item<u16> = Item::new("state_version");
+pub const CURRENT_STATE_VERSION: u16 = 2;
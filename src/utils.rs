@@ -1,28 +1,97 @@
 use abstract_core::objects::UncheckedContractEntry;
-use abstract_sdk::{feature_objects::AnsHost, prelude::*, AbstractSdkResult};
-use cosmwasm_std::{coin, Addr, Api, Coin, Deps, QuerierWrapper};
-use croncat_sdk_manager::msg::ManagerQueryMsg;
+use abstract_sdk::{features::AbstractNameService, prelude::*, AbstractSdkResult};
+use cosmwasm_std::{
+    coin, from_slice, to_vec, wasm_execute, Addr, Api, BankMsg, Binary, BlockInfo, Coin, CosmosMsg,
+    Deps, Env, StdError, StdResult, Storage, Timestamp, WasmMsg,
+};
+use croncat_integration_utils::{
+    error::CronCatContractError,
+    task_creation::{get_croncat_contract, get_latest_croncat_contract},
+    CronCatTaskRequest,
+};
+use croncat_sdk_manager::msg::{ManagerExecuteMsg, ManagerQueryMsg};
+use croncat_sdk_manager::types::{Config as ManagerConfig, TaskBalanceResponse};
+use croncat_sdk_tasks::types::{
+    Action, Boundary, BoundaryHeight, BoundaryTime, Interval, SlotType,
+};
 use cw20::Cw20CoinVerified;
-use cw_asset::{AssetError, AssetInfoBase, AssetListUnchecked};
+use cw_asset::{Asset, AssetInfoBase, AssetList, AssetListUnchecked};
+use serde_cw_value::Value;
 
-use crate::{contract::CroncatApp, error::AppError, CRON_CAT_FACTORY};
+use crate::{
+    contract::CroncatApp,
+    error::AppError,
+    msg::PreviewScheduleResponse,
+    state::{
+        ActionMsgType, ActionPolicy, SpendLimit, SpendingWindow, ACTIVE_TASKS, CONFIG, GROUP_TASKS,
+        MAX_SPENDING_DEPOSITS, SPENDING_WINDOWS, TAG_SEPARATOR, TASK_GROUPS, TASK_HASHES,
+        TASK_LEDGERS, TASK_METAS, TASK_TEMPLATES,
+    },
+    CRON_CAT_FACTORY,
+};
 
-// Check if module is installed on the account
+// Check if module is installed on the account, returns its module id
 pub(crate) fn assert_module_installed(
     deps: Deps,
     contract_addr: &Addr,
     app: &CroncatApp,
-) -> AbstractSdkResult<()> {
+) -> AbstractSdkResult<String> {
     let contract_version = cw2::query_contract_info(&deps.querier, contract_addr)?;
     let modules = app.modules(deps);
     let module_addr = modules.module_address(&contract_version.contract)?;
     if module_addr != contract_addr {
         Err(abstract_core::AbstractError::AppNotInstalled(contract_version.contract).into())
+    } else {
+        Ok(contract_version.contract)
+    }
+}
+
+// Error if the app is paused
+pub(crate) fn assert_not_paused(deps: Deps) -> Result<(), AppError> {
+    if CONFIG.load(deps.storage)?.paused {
+        Err(AppError::Paused {})
     } else {
         Ok(())
     }
 }
 
+// Check task tag against the tag rules from the config
+pub(crate) fn validate_task_tag(deps: Deps, task_tag: &str) -> Result<(), AppError> {
+    let Some(rules) = CONFIG.load(deps.storage)?.tag_rules else {
+        return Ok(());
+    };
+    let invalid = |reason: String| AppError::InvalidTaskTag {
+        task_tag: task_tag.to_owned(),
+        reason,
+    };
+    if task_tag.len() > rules.max_len as usize {
+        return Err(invalid(format!("longer than {} bytes", rules.max_len)));
+    }
+    for segment in task_tag.split(TAG_SEPARATOR) {
+        if segment.is_empty() {
+            return Err(invalid("empty segment".to_owned()));
+        }
+        if let Some(c) = segment
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && !rules.allowed_chars.contains(*c))
+        {
+            return Err(invalid(format!("character {c:?} is not allowed")));
+        }
+    }
+    Ok(())
+}
+
+// Check if module passes the creator filter from the config
+pub(crate) fn assert_creator_allowed(deps: Deps, module_id: String) -> Result<(), AppError> {
+    let config = CONFIG.load(deps.storage)?;
+    match config.creator_filter {
+        Some(filter) if !filter.is_allowed(&module_id) => {
+            Err(AppError::CreatorNotAllowed { module_id })
+        }
+        _ => Ok(()),
+    }
+}
+
 // Check if user balance non empty
 pub(crate) fn user_balance_nonempty(
     deps: Deps,
@@ -41,12 +110,109 @@ pub(crate) fn user_balance_nonempty(
     Ok(!coins.is_empty())
 }
 
-// Sort assetlist to coins and cw20s
-pub(crate) fn sort_funds(
+// Validate contract addresses of the action policy
+pub(crate) fn validate_action_policy(
     api: &dyn Api,
-    assets: AssetListUnchecked,
-) -> Result<(Vec<Coin>, Vec<Cw20CoinVerified>), AssetError> {
-    let assets = assets.check(api, None)?;
+    policy: ActionPolicy,
+) -> StdResult<ActionPolicy> {
+    let allowed_contracts = policy
+        .allowed_contracts
+        .map(|contracts| {
+            contracts
+                .iter()
+                .map(|addr| api.addr_validate(addr.as_str()))
+                .collect::<StdResult<Vec<Addr>>>()
+        })
+        .transpose()?;
+    Ok(ActionPolicy {
+        allowed_contracts,
+        ..policy
+    })
+}
+
+// Check task actions against the action policy
+pub(crate) fn assert_actions_allowed(
+    policy: &ActionPolicy,
+    actions: &[Action],
+    account_addrs: &[Addr],
+    croncat_addrs: &[Addr],
+) -> Result<(), AppError> {
+    for (action_idx, action) in actions.iter().enumerate() {
+        let not_allowed = |reason: String| AppError::ActionNotAllowed { action_idx, reason };
+
+        // Croncat only executes bank sends and wasm executes
+        let (msg_type, target_contract, funds) = match &action.msg {
+            CosmosMsg::Bank(BankMsg::Send { amount, .. }) => (ActionMsgType::Bank, None, amount),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                funds,
+                ..
+            }) => (ActionMsgType::Wasm, Some(contract_addr), funds),
+            _ => return Err(not_allowed("unsupported message type".to_owned())),
+        };
+
+        if !policy.allowed_msg_types.contains(&msg_type) {
+            return Err(not_allowed(format!(
+                "{msg_type:?} messages are not allowed"
+            )));
+        }
+
+        if let Some(contract_addr) = target_contract {
+            if !policy.allow_account_calls && account_addrs.iter().any(|a| a == contract_addr) {
+                return Err(not_allowed(format!("calls to the account {contract_addr}")));
+            }
+            if !policy.allow_croncat_calls && croncat_addrs.iter().any(|a| a == contract_addr) {
+                return Err(not_allowed(format!("calls to croncat {contract_addr}")));
+            }
+            if let Some(allowed_contracts) = &policy.allowed_contracts {
+                if !allowed_contracts.iter().any(|a| a == contract_addr) {
+                    return Err(not_allowed(format!("calls to {contract_addr}")));
+                }
+            }
+        }
+
+        if let Some(max_amounts) = &policy.max_native_amounts {
+            for coin in funds {
+                let max_amount = max_amounts
+                    .iter()
+                    .find(|max| max.denom == coin.denom)
+                    .map(|max| max.amount)
+                    .unwrap_or_default();
+                if coin.amount > max_amount {
+                    return Err(not_allowed(format!(
+                        "{coin} exceeds maximum of {max_amount}{}",
+                        coin.denom
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Withdraw messages for every manager that holds user balance of the proxy
+pub(crate) fn user_withdraw_msgs(
+    deps: Deps,
+    proxy_addr: &Addr,
+    manager_addrs: Vec<Addr>,
+) -> Result<Vec<CosmosMsg>, AppError> {
+    let mut withdraw_msgs = vec![];
+    for manager_addr in manager_addrs {
+        if user_balance_nonempty(deps, proxy_addr.clone(), manager_addr.clone())? {
+            let withdraw_msg: CosmosMsg = wasm_execute(
+                manager_addr,
+                &ManagerExecuteMsg::UserWithdraw { limit: None },
+                vec![],
+            )?
+            .into();
+            withdraw_msgs.push(withdraw_msg);
+        }
+    }
+    Ok(withdraw_msgs)
+}
+
+// Sort assetlist to coins and cw20s
+pub(crate) fn sort_funds(assets: AssetList) -> (Vec<Coin>, Vec<Cw20CoinVerified>) {
     let (funds, cw20s) =
         assets
             .into_iter()
@@ -61,14 +227,475 @@ pub(crate) fn sort_funds(
                 }
                 (funds, cw20s)
             });
-    Ok((funds, cw20s))
+    (funds, cw20s)
+}
+
+// Split assets proportionally to the weights, rounding down
+pub(crate) fn split_assets(assets: &AssetList, weights: &[u64]) -> Vec<AssetList> {
+    let total: u64 = weights.iter().sum();
+    weights
+        .iter()
+        .map(|weight| {
+            let shares: Vec<Asset> = assets
+                .into_iter()
+                .map(|asset| {
+                    Asset::new(
+                        asset.info.clone(),
+                        asset.amount.multiply_ratio(*weight, total),
+                    )
+                })
+                .filter(|asset| !asset.amount.is_zero())
+                .collect();
+            shares.into()
+        })
+        .collect()
+}
+
+// Replace `{{name}}` placeholders outside of json strings with the result of `value_of`
+fn fill_raw_placeholders(
+    template: &str,
+    mut value_of: impl FnMut(&str) -> Result<String, AppError>,
+) -> Result<String, AppError> {
+    let mut filled = String::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut rest = template;
+    while let Some(c) = rest.chars().next() {
+        if !in_string && rest.starts_with("{{") {
+            if let Some(len) = rest[2..].find("}}") {
+                filled.push_str(&value_of(&rest[2..2 + len])?);
+                rest = &rest[2 + len + 2..];
+                continue;
+            }
+        }
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            _ => {}
+        }
+        filled.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    Ok(filled)
+}
+
+// Parse json of the task template, placeholders outside of json strings are filled by `value_of`
+fn parse_template(
+    template: &Binary,
+    value_of: impl FnMut(&str) -> Result<String, AppError>,
+) -> Result<Value, AppError> {
+    let invalid = |reason: String| AppError::InvalidTemplate { reason };
+    let template = std::str::from_utf8(template).map_err(|e| invalid(e.to_string()))?;
+    let filled = fill_raw_placeholders(template, value_of)?;
+    match from_slice(filled.as_bytes()) {
+        Ok(value @ Value::Map(_)) => Ok(value),
+        Ok(_) => Err(invalid("task must be a json object".to_owned())),
+        Err(e) => Err(invalid(e.to_string())),
+    }
+}
+
+// Check that the template is a json object
+pub(crate) fn validate_template(template: &Binary) -> Result<(), AppError> {
+    parse_template(template, |_| Ok("0".to_owned())).map(|_| ())
+}
+
+// Value of the param, names of the used params are added to `found`
+fn param_value<'a>(
+    params: &'a [(String, String)],
+    name: &str,
+    found: &mut Vec<String>,
+) -> Result<&'a str, AppError> {
+    let (_, value) = params
+        .iter()
+        .find(|(param, _)| param == name)
+        .ok_or_else(|| AppError::InvalidTemplateParams {
+            reason: format!("missing param {name}"),
+        })?;
+    if !found.iter().any(|found| found == name) {
+        found.push(name.to_owned());
+    }
+    Ok(value)
+}
+
+// Replace `{{name}}` placeholders of the string in one pass, so values are never expanded.
+// Names of the found placeholders are added to `found`
+fn fill_placeholders(
+    template: &str,
+    params: &[(String, String)],
+    found: &mut Vec<String>,
+) -> Result<String, AppError> {
+    let mut filled = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let value = param_value(params, &rest[start + 2..start + 2 + len], found)?;
+        filled.push_str(&rest[..start]);
+        filled.push_str(value);
+        rest = &rest[start + 2 + len + 2..];
+    }
+    filled.push_str(rest);
+    Ok(filled)
+}
+
+// Fill placeholders in every string of the json value
+fn fill_value(
+    value: Value,
+    params: &[(String, String)],
+    found: &mut Vec<String>,
+) -> Result<Value, AppError> {
+    let filled = match value {
+        Value::String(string) => Value::String(fill_placeholders(&string, params, found)?),
+        Value::Seq(values) => Value::Seq(
+            values
+                .into_iter()
+                .map(|value| fill_value(value, params, found))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Map(entries) => Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| Ok((key, fill_value(value, params, found)?)))
+                .collect::<Result<_, AppError>>()?,
+        ),
+        value => value,
+    };
+    Ok(filled)
+}
+
+// Field of the json object
+fn json_field<'a>(value: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    match value {
+        Value::Map(entries) => entries.get_mut(&Value::String(key.to_owned())),
+        _ => None,
+    }
+}
+
+// Fill every placeholder of the template task with the params
+pub(crate) fn fill_template(
+    template: &Binary,
+    params: &[(String, String)],
+) -> Result<CronCatTaskRequest, AppError> {
+    let invalid = |reason: String| AppError::InvalidTemplateParams { reason };
+    let mut found = vec![];
+    // Placeholders outside of json strings take numbers and booleans
+    let task = parse_template(template, |name| {
+        let value = param_value(params, name, &mut found)?;
+        match from_slice(value.as_bytes()) {
+            Ok(Value::Bool(_) | Value::U64(_) | Value::I64(_)) => Ok(value.to_owned()),
+            _ => Err(invalid(format!(
+                "param {name} must be a number or a boolean"
+            ))),
+        }
+    })?;
+    let mut task = fill_value(task, params, &mut found)?;
+    if let Some((name, _)) = params.iter().find(|(name, _)| !found.contains(name)) {
+        return Err(invalid(format!("unknown param {name}")));
+    }
+
+    // Wasm messages given as json objects are encoded to binary
+    if let Some(Value::Seq(actions)) = json_field(&mut task, "actions") {
+        for action in actions {
+            let wasm_msg = json_field(action, "msg")
+                .and_then(|msg| json_field(msg, "wasm"))
+                .and_then(|wasm| json_field(wasm, "execute"))
+                .and_then(|execute| json_field(execute, "msg"));
+            if let Some(msg @ Value::Map(_)) = wasm_msg {
+                *msg = Value::String(Binary(to_vec(msg)?).to_base64());
+            }
+        }
+    }
+    from_slice(&to_vec(&task)?).map_err(|e| invalid(e.to_string()))
+}
+
+// Remove task from the state along with its group membership
+pub(crate) fn remove_active_task(storage: &mut dyn Storage, key: (Addr, String)) -> StdResult<()> {
+    if let Some(task) = ACTIVE_TASKS.may_load(storage, key.clone())? {
+        TASK_HASHES.remove(storage, &task.task_hash);
+    }
+    ACTIVE_TASKS.remove(storage, key.clone());
+    TASK_TEMPLATES.remove(storage, key.clone());
+    TASK_METAS.remove(storage, key.clone());
+    if let Some(group) = TASK_GROUPS.may_load(storage, key.clone())? {
+        GROUP_TASKS.remove(storage, (key.0.clone(), group, key.1.clone()));
+        TASK_GROUPS.remove(storage, key);
+    }
+    Ok(())
+}
+
+// Validate assets of the spend limit
+pub(crate) fn validate_spend_limit(api: &dyn Api, limit: SpendLimit) -> StdResult<SpendLimit> {
+    let assets = AssetListUnchecked::from(limit.assets)
+        .check(api, None)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    Ok(SpendLimit {
+        assets,
+        period: limit.period,
+    })
+}
+
+// Spending window of the creator, without deposits older than the period
+pub(crate) fn current_spending_window(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    creator_addr: &Addr,
+    period: u64,
+) -> StdResult<SpendingWindow> {
+    let mut window = SPENDING_WINDOWS
+        .may_load(storage, creator_addr)?
+        .unwrap_or_default();
+    window
+        .deposits
+        .retain(|(time, _)| block.time < time.plus_seconds(period));
+    Ok(window)
+}
+
+// Add deposit to the spending window of the creator, erroring if it goes over the spend limit
+pub(crate) fn record_spending(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    creator_addr: &Addr,
+    assets: &AssetList,
+) -> Result<(), AppError> {
+    let Some(limit) = CONFIG.load(storage)?.spend_limit else {
+        return Ok(());
+    };
+    let mut window = current_spending_window(storage, block, creator_addr, limit.period)?;
+    let spent = window.spent()?;
+    for asset in assets {
+        if let Some(max) = limit.assets.find(&asset.info) {
+            let spent = spent
+                .find(&asset.info)
+                .map(|spent| spent.amount)
+                .unwrap_or_default();
+            if spent.checked_add(asset.amount)? > max.amount {
+                let remaining = Asset::new(max.info.clone(), max.amount.saturating_sub(spent));
+                return Err(AppError::SpendLimitExceeded {
+                    remaining: remaining.to_string(),
+                });
+            }
+        }
+    }
+    window.deposits.push((block.time, assets.clone()));
+    // Merged deposits are released together with the newer one
+    if window.deposits.len() > MAX_SPENDING_DEPOSITS {
+        let (_, oldest) = window.deposits.remove(0);
+        window.deposits[0].1.add_many(&oldest)?;
+    }
+    SPENDING_WINDOWS.save(storage, creator_addr, &window)?;
+    Ok(())
+}
+
+// Add deposit to the task ledger, errors if it goes over the budget cap
+pub(crate) fn record_deposit(
+    storage: &mut dyn Storage,
+    key: (Addr, String),
+    assets: &AssetList,
+) -> Result<(), AppError> {
+    let mut ledger = TASK_LEDGERS
+        .may_load(storage, key.clone())?
+        .unwrap_or_default();
+    if let Some(cap) = &ledger.cap {
+        for asset in assets {
+            if let Some(max) = cap.find(&asset.info) {
+                let deposited = ledger
+                    .deposited
+                    .find(&asset.info)
+                    .map(|deposited| deposited.amount)
+                    .unwrap_or_default();
+                if deposited.checked_add(asset.amount)? > max.amount {
+                    let remaining =
+                        Asset::new(max.info.clone(), max.amount.saturating_sub(deposited));
+                    return Err(AppError::BudgetExceeded {
+                        remaining: remaining.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    ledger.deposited.add_many(assets)?;
+    TASK_LEDGERS.save(storage, key, &ledger)?;
+    Ok(())
+}
+
+// Balance of the task on croncat manager, none if the task has no balance
+pub(crate) fn task_balance_assets(
+    deps: Deps,
+    manager_addr: &Addr,
+    task_hash: String,
+) -> Result<Option<AssetList>, AppError> {
+    let balance_response: TaskBalanceResponse = deps
+        .querier
+        .query_wasm_smart(manager_addr, &ManagerQueryMsg::TaskBalance { task_hash })?;
+    let Some(balance) = balance_response.balance else {
+        return Ok(None);
+    };
+    let manager_config: ManagerConfig = deps
+        .querier
+        .query_wasm_smart(manager_addr, &ManagerQueryMsg::Config {})?;
+
+    let mut assets = AssetList::new();
+    if !balance.native_balance.is_zero() {
+        assets.add(&Asset::native(
+            manager_config.native_denom,
+            balance.native_balance,
+        ))?;
+    }
+    if let Some(cw20) = balance.cw20_balance {
+        assets.add(&Asset::cw20(cw20.address, cw20.amount))?;
+    }
+    if let Some(ibc) = balance.ibc_balance {
+        assets.add(&Asset::native(ibc.denom, ibc.amount))?;
+    }
+    Ok(Some(assets))
 }
 
-pub(crate) fn factory_addr(
-    querier: &QuerierWrapper,
-    ans_host: &AnsHost,
-) -> Result<Addr, crate::error::AppError> {
+// Get croncat factory address from the config override or from the ANS
+pub(crate) fn factory_addr(deps: Deps, app: &CroncatApp) -> Result<Addr, AppError> {
+    if let Some(factory_addr) = CONFIG.load(deps.storage)?.factory_addr {
+        return Ok(factory_addr);
+    }
     let factory_entry = UncheckedContractEntry::try_from(CRON_CAT_FACTORY.to_owned())?.into();
-    let factory_addr = ans_host.query_contract(querier, &factory_entry)?;
+    let factory_addr = app
+        .ans_host(deps)?
+        .query_contract(&deps.querier, &factory_entry)?;
     Ok(factory_addr)
 }
+
+// Only cw20s whitelisted on the croncat manager are trusted to report received amounts
+pub(crate) fn assert_cw20_whitelisted(
+    deps: Deps,
+    manager_addr: &Addr,
+    cw20_addr: &Addr,
+) -> Result<(), AppError> {
+    let manager_config: ManagerConfig = deps
+        .querier
+        .query_wasm_smart(manager_addr, &ManagerQueryMsg::Config {})?;
+    if !manager_config.cw20_whitelist.contains(cw20_addr) {
+        return Err(AppError::Cw20NotWhitelisted {
+            cw20_addr: cw20_addr.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// Get croncat contract address of the version pinned in the config, or the latest one
+pub(crate) fn new_task_croncat_contract(
+    deps: Deps,
+    factory_addr: Addr,
+    contract_name: &str,
+) -> Result<Addr, AppError> {
+    let Some([major, minor]) = CONFIG.load(deps.storage)?.croncat_version else {
+        return Ok(get_latest_croncat_contract(
+            &deps.querier,
+            factory_addr,
+            contract_name.to_owned(),
+        )?);
+    };
+    let version = format!("{major}.{minor}");
+    get_croncat_contract(
+        &deps.querier,
+        factory_addr,
+        contract_name.to_owned(),
+        version.clone(),
+    )
+    .map_err(|e| match e {
+        CronCatContractError::NoSuchContractOnFactory { .. } => {
+            AppError::PinnedVersionNotFound { version }
+        }
+        e => e.into(),
+    })
+}
+
+// Fill in the task boundary the same way croncat tasks contract does on task creation
+pub(crate) fn task_boundary(
+    block: &BlockInfo,
+    interval: &Interval,
+    boundary: Option<Boundary>,
+) -> Result<Boundary, AppError> {
+    match (interval, boundary) {
+        (Interval::Cron(_) | Interval::Once, Some(Boundary::Time(boundary_time))) => {
+            let start = boundary_time.start.unwrap_or(block.time);
+            if boundary_time.end.is_some_and(|end| end <= start) {
+                Err(AppError::InvalidBoundary {})
+            } else {
+                Ok(Boundary::Time(boundary_time))
+            }
+        }
+        (
+            Interval::Once | Interval::Immediate | Interval::Block(_),
+            Some(Boundary::Height(boundary_height)),
+        ) => {
+            let start = boundary_height.start.map_or(block.height, |s| s.u64());
+            if boundary_height.end.is_some_and(|end| end.u64() <= start) {
+                Err(AppError::InvalidBoundary {})
+            } else {
+                Ok(Boundary::Height(boundary_height))
+            }
+        }
+        (Interval::Cron(_), None) => Ok(Boundary::Time(BoundaryTime {
+            start: None,
+            end: None,
+        })),
+        (_, None) => Ok(Boundary::Height(BoundaryHeight {
+            start: None,
+            end: None,
+        })),
+        _ => Err(AppError::InvalidBoundary {}),
+    }
+}
+
+// Compute upcoming execution slots of the task, following croncat rescheduling rules
+pub(crate) fn preview_schedule(
+    env: &Env,
+    interval: &Interval,
+    boundary: &Boundary,
+    has_queries: bool,
+    slot_granularity_time: u64,
+    count: usize,
+) -> Result<PreviewScheduleResponse, AppError> {
+    if !interval.is_valid() {
+        return Err(AppError::InvalidInterval {});
+    }
+    let evented = has_queries && matches!(interval, Interval::Once | Interval::Immediate);
+    let slot_type = if boundary.is_block() {
+        SlotType::Block
+    } else {
+        SlotType::Cron
+    };
+
+    let mut env = env.clone();
+    let mut slots: Vec<u64> = Vec::with_capacity(count);
+    while slots.len() < count {
+        let (next_id, _) = interval.next(&env, boundary, slot_granularity_time);
+        // Zero means task ended, non-increasing slot means it got clamped to the boundary end
+        if next_id == 0 || slots.last().is_some_and(|&last| next_id <= last) {
+            break;
+        }
+        // Manager won't execute the task in this slot if it's after boundary end
+        let after_end = match boundary {
+            Boundary::Height(BoundaryHeight { end: Some(end), .. }) => {
+                end.u64() < next_id.saturating_sub(1)
+            }
+            Boundary::Time(BoundaryTime { end: Some(end), .. }) => end.nanos() <= next_id,
+            _ => false,
+        };
+        if after_end {
+            break;
+        }
+        slots.push(next_id);
+
+        // Evented tasks are not rescheduled into slots, they wait for their queries instead
+        if *interval == Interval::Once || evented {
+            break;
+        }
+        match slot_type {
+            SlotType::Block => env.block.height = next_id,
+            SlotType::Cron => env.block.time = Timestamp::from_nanos(next_id),
+        }
+    }
+
+    Ok(PreviewScheduleResponse { slot_type, slots })
+}
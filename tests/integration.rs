@@ -4,24 +4,31 @@ use std::cell::RefMut;
 
 use abstract_core::{
     ans_host::ContractsResponse,
-    app::BaseInstantiateMsg,
-    objects::{gov_type::GovernanceDetails, UncheckedContractEntry},
+    app::{BaseInstantiateMsg, BaseMigrateMsg},
+    manager::InternalConfigAction,
+    objects::{gov_type::GovernanceDetails, AnsAsset, AssetEntry, UncheckedContractEntry},
 };
 use abstract_interface::{Abstract, AbstractAccount, AppDeployer, VCExecFns};
 
 use common::contracts;
 use croncat_app::{
     contract::{CRONCAT_ID, CRONCAT_MODULE_VERSION},
+    dca_task_request, dca_task_tag,
     error::AppError,
     msg::{
-        ActiveTasksByCreatorResponse, ActiveTasksResponse, AppInstantiateMsg, ConfigResponse,
-        InstantiateMsg,
+        ActiveTasksByCreatorResponse, ActiveTasksResponse, AppExecuteMsg, AppInstantiateMsg,
+        AppMigrateMsg, AppReceiveMsg, ArchivedTasksResponse, ConfigField, ConfigResponse,
+        CreationMode, ExecuteMsg, GroupSplit, GroupTasksResponse, InstantiateMsg, MigrateMsg,
+        PreviewScheduleResponse, SpendingAllowanceResponse, SponsorshipsResponse, TaskCostResponse,
+    },
+    state::{
+        ActionMsgType, ActionPolicy, Config, CreatorFilter, SpendLimit, TagRules, TaskMeta,
+        Template, TemplateInstance, MAX_SPENDING_DEPOSITS,
     },
-    state::Config,
     AppExecuteMsgFns, AppQueryMsgFns, CroncatApp, CRON_CAT_FACTORY,
 };
 
-use croncat_integration_utils::{AGENTS_NAME, MANAGER_NAME, TASKS_NAME};
+use croncat_integration_utils::{CronCatTaskExecutionInfo, AGENTS_NAME, MANAGER_NAME, TASKS_NAME};
 use croncat_sdk_agents::msg::InstantiateMsg as AgentsInstantiateMsg;
 use croncat_sdk_factory::msg::{
     ContractMetadataResponse, FactoryInstantiateMsg, FactoryQueryMsg, ModuleInstantiateInfo,
@@ -33,16 +40,20 @@ use croncat_sdk_manager::{
 };
 use croncat_sdk_tasks::{
     msg::TasksInstantiateMsg,
-    types::{Action, TaskRequest, TaskResponse},
+    types::{Action, Boundary, BoundaryHeight, SlotType, TaskRequest, TaskResponse},
 };
 
-use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
 use cw_asset::{Asset, AssetList, AssetListUnchecked};
 use cw_multi_test::Executor;
 // Use prelude to get all the necessary imports
 use cw_orch::{anyhow, deploy::Deploy, prelude::*};
 
-use cosmwasm_std::{coins, to_binary, Addr, BankMsg, Uint128, WasmMsg};
+use cosmwasm_std::{
+    coins, from_binary, testing::MockStorage, to_binary, Addr, BankMsg, Binary, Event, Order,
+    Storage, Uint128, WasmMsg,
+};
+use cw_storage_plus::{Item, Map};
 
 use crate::common::contracts::TasksResponseCaster;
 // consts for testing
@@ -271,7 +282,15 @@ fn setup() -> anyhow::Result<TestingSetup> {
             base: BaseInstantiateMsg {
                 ans_host_address: abstr_deployment.ans_host.addr_str()?,
             },
-            module: AppInstantiateMsg {},
+            module: AppInstantiateMsg {
+                factory_addr: None,
+                croncat_version: None,
+                creator_filter: None,
+                action_policy: None,
+                spend_limit: None,
+                pauser: None,
+                tag_rules: None,
+            },
         },
         None,
     )?;
@@ -348,7 +367,7 @@ fn all_in_one() -> anyhow::Result<()> {
     };
     let task_tag = "test_sends".to_owned();
     module_contract
-        .create_task(assets, Box::new(task), task_tag)
+        .create_task(assets, Box::new(task), task_tag, None, None, &[])
         .unwrap();
 
     let active_tasks_response: ActiveTasksResponse =
@@ -375,7 +394,7 @@ fn all_in_one() -> anyhow::Result<()> {
         AssetListUnchecked::from(assets)
     };
     module_contract
-        .refill_task(assets, active_tasks[0].1.clone())
+        .refill_task(assets, active_tasks[0].1.clone(), None)
         .unwrap();
     let task_balance2: TaskBalance = module_contract
         .task_balance(active_tasks[0].0.to_string(), active_tasks[0].1.clone())?
@@ -423,7 +442,7 @@ fn all_in_one() -> anyhow::Result<()> {
     )?;
     assert!(manager_cw20_balance.balance.is_zero());
 
-    module_contract.remove_task(active_tasks[0].1.clone())?;
+    module_contract.remove_task(active_tasks[0].1.clone(), None)?;
 
     // After task is removed check all balances got not here
     let module_balance = mock.query_balance(&module_contract.address()?, DENOM)?;
@@ -496,19 +515,27 @@ fn admin() -> anyhow::Result<()> {
     }
     .to_string();
 
-    let err = module_contract.update_config();
+    let err = module_contract.update_config(None, None, None, None, None, None, None, None);
     assert_eq!(
         err.unwrap_err().root().to_string(),
         cw_controllers::AdminError::NotAdmin {}.to_string()
     );
     let task_tag = "test_tag".to_owned();
-    let err = module_contract.create_task(AssetListUnchecked::default(), Box::new(task), task_tag);
+    let err = module_contract.create_task(
+        AssetListUnchecked::default(),
+        Box::new(task),
+        task_tag,
+        None,
+        None,
+        &[],
+    );
     assert_eq!(err.unwrap_err().root().to_string(), expected_err);
 
-    let err = module_contract.remove_task("aloha:321".to_owned());
+    let err = module_contract.remove_task("aloha:321".to_owned(), None);
     assert_eq!(err.unwrap_err().root().to_string(), expected_err);
 
-    let err = module_contract.refill_task(AssetListUnchecked::default(), "woof:123".to_owned());
+    let err =
+        module_contract.refill_task(AssetListUnchecked::default(), "woof:123".to_owned(), None);
     assert_eq!(err.unwrap_err().root().to_string(), expected_err);
 
     Ok(())
@@ -523,12 +550,186 @@ fn update_config() -> anyhow::Result<()> {
 
     let config_res: ConfigResponse = module_contract.config()?;
 
-    assert_eq!(config_res.config, Config {});
+    assert_eq!(
+        config_res.config,
+        Config {
+            factory_addr: None,
+            croncat_version: None,
+            creator_filter: None,
+            action_policy: None,
+            spend_limit: None,
+            pauser: None,
+            tag_rules: None,
+            paused: false,
+        }
+    );
+
+    module_contract.update_config(None, None, None, None, None, None, None, None)?;
+
+    let config_res: ConfigResponse = module_contract.config()?;
+    assert_eq!(config_res.config, Config::default());
+
+    // Fields that are not set are kept
+    let spend_limit = SpendLimit {
+        assets: AssetList::from(coins(50_000, DENOM)),
+        period: 86_400,
+    };
+    module_contract.update_config(
+        None,
+        Some(CreatorFilter::Deny(vec!["abstract:dex".to_owned()])),
+        None,
+        None,
+        Some("pauser".to_owned()),
+        Some(spend_limit.clone()),
+        None,
+        None,
+    )?;
+    module_contract.update_config(None, None, Some([1, 0]), None, None, None, None, None)?;
+    let config_res: ConfigResponse = module_contract.config()?;
+    assert_eq!(
+        config_res.config,
+        Config {
+            factory_addr: None,
+            croncat_version: Some([1, 0]),
+            creator_filter: Some(CreatorFilter::Deny(vec!["abstract:dex".to_owned()])),
+            action_policy: None,
+            spend_limit: Some(spend_limit.clone()),
+            pauser: Some(Addr::unchecked("pauser")),
+            tag_rules: None,
+            paused: false,
+        }
+    );
+
+    // Unset fields are reset
+    module_contract.update_config(
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(vec![ConfigField::CreatorFilter, ConfigField::Pauser]),
+    )?;
+    let config_res: ConfigResponse = module_contract.config()?;
+    assert_eq!(
+        config_res.config,
+        Config {
+            factory_addr: None,
+            croncat_version: Some([1, 0]),
+            creator_filter: None,
+            action_policy: None,
+            spend_limit: Some(spend_limit),
+            pauser: None,
+            tag_rules: None,
+            paused: false,
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn factory_addr_override() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract,
+        abstr_deployment,
+        ..
+    } = setup()?;
 
-    module_contract.update_config()?;
+    let factory_entry = UncheckedContractEntry::try_from(CRON_CAT_FACTORY.to_owned())?;
+    let contracts_response: ContractsResponse =
+        abstr_deployment
+            .ans_host
+            .query(&abstract_core::ans_host::QueryMsg::Contracts {
+                entries: vec![factory_entry.clone().into()],
+            })?;
+    let factory_addr: Addr = contracts_response.contracts[0].1.clone();
 
+    module_contract.update_config(
+        None,
+        None,
+        None,
+        Some(factory_addr.to_string()),
+        None,
+        None,
+        None,
+        None,
+    )?;
     let config_res: ConfigResponse = module_contract.config()?;
-    assert_eq!(config_res.config, Config {});
+    assert_eq!(
+        config_res.config,
+        Config {
+            factory_addr: Some(factory_addr),
+            croncat_version: None,
+            creator_filter: None,
+            action_policy: None,
+            spend_limit: None,
+            pauser: None,
+            tag_rules: None,
+            paused: false,
+        }
+    );
+
+    // Remove factory from the ANS, app should still work
+    abstr_deployment.ans_host.execute(
+        &abstract_core::ans_host::ExecuteMsg::UpdateContractAddresses {
+            to_add: vec![],
+            to_remove: vec![factory_entry],
+        },
+        None,
+    )?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let task_tag = "test_tag".to_owned();
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+    module_contract.create_task(assets, Box::new(task), task_tag.clone(), None, None, &[])?;
+
+    let active_tasks_response: ActiveTasksResponse =
+        module_contract.active_tasks(Some(true), None, None)?;
+    let (scheduled_tasks, removed_tasks) = active_tasks_response.checked();
+    assert_eq!(scheduled_tasks.len(), 1);
+    assert!(removed_tasks.is_empty());
+
+    let (creator_addr, _) = scheduled_tasks[0].clone();
+    let task_balance: TaskBalanceResponse =
+        module_contract.task_balance(creator_addr.to_string(), task_tag.clone())?;
+    assert_eq!(
+        task_balance.balance.unwrap().native_balance,
+        Uint128::new(45_000)
+    );
+    let assets = AssetListUnchecked::from(AssetList::from(coins(100, DENOM)));
+    module_contract.refill_task(assets, task_tag.clone(), None)?;
+    module_contract.remove_task(task_tag, None)?;
+
+    // Without the override app is unusable
+    module_contract.update_config(
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(vec![ConfigField::FactoryAddr]),
+    )?;
+    let err = module_contract.active_tasks(Some(true), None, None);
+    assert!(err.is_err());
     Ok(())
 }
 
@@ -562,7 +763,7 @@ fn create_task() -> anyhow::Result<()> {
     };
     let task_tag = "test_tag".to_owned();
     let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
-    module_contract.create_task(assets, Box::new(task), task_tag.clone())?;
+    module_contract.create_task(assets, Box::new(task), task_tag.clone(), None, None, &[])?;
 
     let active_tasks_response: ActiveTasksResponse =
         module_contract.active_tasks(None, None, None)?;
@@ -611,13 +812,20 @@ fn create_task() -> anyhow::Result<()> {
         ))?;
         AssetListUnchecked::from(assets)
     };
-    let err = module_contract.create_task(assets.clone(), Box::new(task.clone()), task_tag.clone());
+    let err = module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        task_tag.clone(),
+        None,
+        None,
+        &[],
+    );
     assert_eq!(
         err.unwrap_err().root().to_string(),
         AppError::TaskAlreadyExists { task_tag }.to_string()
     );
     let task_tag = "test_tag2".to_owned();
-    module_contract.create_task(assets, Box::new(task), task_tag)?;
+    module_contract.create_task(assets, Box::new(task), task_tag, None, None, &[])?;
 
     let active_tasks_response: ActiveTasksResponse =
         module_contract.active_tasks(None, None, None)?;
@@ -647,7 +855,7 @@ fn create_task() -> anyhow::Result<()> {
     };
     let task_tag = "test_tag3".to_owned();
     let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
-    module_contract.create_task(assets, Box::new(task), task_tag)?;
+    module_contract.create_task(assets, Box::new(task), task_tag, None, None, &[])?;
 
     let active_tasks_response: ActiveTasksResponse =
         module_contract.active_tasks(None, None, None)?;
@@ -701,7 +909,7 @@ fn refill_task() -> anyhow::Result<()> {
         ))?;
         AssetListUnchecked::from(assets)
     };
-    module_contract.create_task(assets, Box::new(task), task_tag)?;
+    module_contract.create_task(assets, Box::new(task), task_tag, None, None, &[])?;
 
     let active_tasks_response: ActiveTasksResponse =
         module_contract.active_tasks(None, None, None)?;
@@ -724,7 +932,7 @@ fn refill_task() -> anyhow::Result<()> {
 
     // Refill only with native coins
     let assets = AssetListUnchecked::from(AssetList::from(coins(123, DENOM)));
-    module_contract.refill_task(assets, task_tag.clone())?;
+    module_contract.refill_task(assets, task_tag.clone(), None)?;
     let task_balance: TaskBalanceResponse =
         module_contract.task_balance(creator_addr.to_string(), task_tag.clone())?;
     assert_eq!(
@@ -748,7 +956,7 @@ fn refill_task() -> anyhow::Result<()> {
         ))?;
         AssetListUnchecked::from(assets)
     };
-    module_contract.refill_task(assets, task_tag.clone())?;
+    module_contract.refill_task(assets, task_tag.clone(), None)?;
     let task_balance: TaskBalanceResponse =
         module_contract.task_balance(creator_addr.to_string(), task_tag.clone())?;
     assert_eq!(
@@ -772,7 +980,7 @@ fn refill_task() -> anyhow::Result<()> {
         ))?;
         AssetListUnchecked::from(assets)
     };
-    module_contract.refill_task(assets, task_tag.clone())?;
+    module_contract.refill_task(assets, task_tag.clone(), None)?;
     let task_balance: TaskBalanceResponse =
         module_contract.task_balance(creator_addr.to_string(), task_tag)?;
     assert_eq!(
@@ -836,7 +1044,7 @@ fn remove_task() -> anyhow::Result<()> {
         ))?;
         AssetListUnchecked::from(assets)
     };
-    module_contract.create_task(assets, Box::new(task), task_tag1)?;
+    module_contract.create_task(assets, Box::new(task), task_tag1, None, None, &[])?;
 
     let cw20_amount = Cw20Coin {
         address: cw20_addr.to_string(),
@@ -871,7 +1079,7 @@ fn remove_task() -> anyhow::Result<()> {
         ))?;
         AssetListUnchecked::from(assets)
     };
-    module_contract.create_task(assets, Box::new(task), task_tag2)?;
+    module_contract.create_task(assets, Box::new(task), task_tag2, None, None, &[])?;
 
     // One of them will be removed by the agent
     {
@@ -926,7 +1134,7 @@ fn remove_task() -> anyhow::Result<()> {
         &cw20_addr,
     )?;
 
-    module_contract.remove_task(not_active_task)?;
+    module_contract.remove_task(not_active_task, None)?;
 
     let proxy_cw20_balance2: cw20::BalanceResponse = mock.query(
         &Cw20QueryMsg::Balance {
@@ -937,7 +1145,7 @@ fn remove_task() -> anyhow::Result<()> {
 
     assert!(proxy_cw20_balance2.balance > proxy_cw20_balance1.balance);
 
-    module_contract.remove_task(active_task)?;
+    module_contract.remove_task(active_task, None)?;
 
     let proxy_cw20_balance3: cw20::BalanceResponse = mock.query(
         &Cw20QueryMsg::Balance {
@@ -956,6 +1164,7 @@ fn purge() -> anyhow::Result<()> {
     let TestingSetup {
         module_contract,
         account,
+        mock,
         ..
     } = setup()?;
 
@@ -978,18 +1187,2565 @@ fn purge() -> anyhow::Result<()> {
     };
     let task_tag = "test_tag".to_owned();
     let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
-    module_contract.create_task(assets, Box::new(task), task_tag)?;
+    module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        task_tag,
+        None,
+        None,
+        &[],
+    )?;
 
     let active_tasks_by_creator_response: ActiveTasksByCreatorResponse =
         module_contract.active_tasks_by_creator(account.manager.addr_str()?, None, None, None)?;
     let tasks = active_tasks_by_creator_response.unchecked();
     assert_eq!(tasks.len(), 1);
 
-    module_contract.purge(tasks)?;
+    module_contract.purge(false, tasks, None)?;
 
     let active_tasks_by_creator_response: ActiveTasksByCreatorResponse =
         module_contract.active_tasks_by_creator(account.manager.addr_str()?, None, None, None)?;
     let tasks = active_tasks_by_creator_response.unchecked();
     assert_eq!(tasks.len(), 0);
+
+    // Purge with recovery
+    let task = TaskRequest {
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "bob".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        ..task
+    };
+    let task_tag = "recover_tag".to_owned();
+    module_contract.create_task(assets, Box::new(task), task_tag.clone(), None, None, &[])?;
+
+    let proxy_balance1 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    module_contract.purge(true, vec![task_tag, "unknown_tag".to_owned()], None)?;
+
+    let proxy_balance2 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    assert_eq!(proxy_balance2, proxy_balance1 + Uint128::new(45_000));
+    let active_tasks_by_creator_response: ActiveTasksByCreatorResponse =
+        module_contract.active_tasks_by_creator(account.manager.addr_str()?, None, None, None)?;
+    assert!(active_tasks_by_creator_response.unchecked().is_empty());
+
+    // Purge messages without `recover` are still accepted
+    let msg: AppExecuteMsg = cosmwasm_std::from_slice(br#"{"purge":{"task_tags":["tag"]}}"#)?;
+    assert_eq!(
+        msg,
+        AppExecuteMsg::Purge {
+            task_tags: vec!["tag".to_owned()],
+            recover: false,
+            creator: None,
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn preview_schedule() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract, ..
+    } = setup()?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Block(10),
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(1, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let task_tag = "test_tag".to_owned();
+    let assets = AssetListUnchecked::from(AssetList::from(coins(60_000, DENOM)));
+    module_contract.create_task(
+        assets,
+        Box::new(task.clone()),
+        task_tag.clone(),
+        None,
+        None,
+        &[],
+    )?;
+
+    let active_tasks_response: ActiveTasksResponse =
+        module_contract.active_tasks(None, None, None)?;
+    let (creator_addr, task_tag) = active_tasks_response.unchecked()[0].clone();
+
+    // Block interval scheduled on the croncat
+    let preview: PreviewScheduleResponse =
+        module_contract.preview_schedule(creator_addr.to_string(), task_tag, Some(3))?;
+    assert_eq!(preview.slot_type, SlotType::Block);
+    assert_eq!(preview.slots.len(), 3);
+    assert_eq!(preview.slots[0] % 10, 0);
+    assert_eq!(preview.slots[1], preview.slots[0] + 10);
+    assert_eq!(preview.slots[2], preview.slots[1] + 10);
+
+    // Cron interval that is not created yet
+    let cron_task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Cron("0 * * * * *".to_owned()),
+        ..task.clone()
+    };
+    let preview: PreviewScheduleResponse =
+        module_contract.preview_task_request_schedule(Box::new(cron_task.clone()), Some(3))?;
+    assert_eq!(preview.slot_type, SlotType::Cron);
+    assert_eq!(preview.slots.len(), 3);
+    assert_eq!(preview.slots[1], preview.slots[0] + 60_000_000_000);
+    assert_eq!(preview.slots[2], preview.slots[1] + 60_000_000_000);
+
+    // Boundary end limits the slots
+    let height = module_contract.get_chain().block_info()?.height;
+    let bounded_task = TaskRequest {
+        boundary: Some(Boundary::Height(BoundaryHeight {
+            start: None,
+            end: Some((height + 25).into()),
+        })),
+        ..task.clone()
+    };
+    let preview: PreviewScheduleResponse =
+        module_contract.preview_task_request_schedule(Box::new(bounded_task), None)?;
+    assert!(!preview.slots.is_empty());
+    assert!(preview.slots.iter().all(|&slot| slot <= height + 25));
+
+    // Once is executed only once
+    let once_task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        ..task
+    };
+    let preview: PreviewScheduleResponse =
+        module_contract.preview_task_request_schedule(Box::new(once_task), None)?;
+    assert_eq!(preview.slots.len(), 1);
+
+    // Cron interval can't have height boundary
+    let invalid_task = TaskRequest {
+        boundary: Some(Boundary::Height(BoundaryHeight {
+            start: None,
+            end: None,
+        })),
+        ..cron_task
+    };
+    let err = module_contract.preview_task_request_schedule(Box::new(invalid_task), None);
+    assert!(err
+        .unwrap_err()
+        .to_string()
+        .contains(&AppError::InvalidBoundary {}.to_string()));
+    Ok(())
+}
+
+#[test]
+fn force_remove_tasks() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        mut module_contract,
+        mock,
+        account,
+        cw20_addr,
+        ..
+    } = setup()?;
+
+    let cw20_amount = Cw20Coin {
+        address: cw20_addr.to_string(),
+        amount: Uint128::new(20),
+    };
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: WasmMsg::Execute {
+                contract_addr: cw20_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "bob".to_owned(),
+                    amount: Uint128::new(20),
+                })?,
+                funds: vec![],
+            }
+            .into(),
+            gas_limit: Some(120),
+        }],
+        queries: None,
+        transforms: None,
+        cw20: Some(cw20_amount),
+    };
+    let assets = {
+        let mut assets = AssetList::from(coins(40_000, DENOM));
+        assets.add(&Asset::cw20(
+            Addr::unchecked(cw20_addr.clone()),
+            Uint128::new(20),
+        ))?;
+        AssetListUnchecked::from(assets)
+    };
+    module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        "test_tag1".to_owned(),
+        None,
+        None,
+        &[],
+    )?;
+
+    // Recurring task needs balance for two executions
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Block(5),
+        cw20: Some(Cw20Coin {
+            address: cw20_addr.to_string(),
+            amount: Uint128::new(40),
+        }),
+        ..task
+    };
+    let assets = {
+        let mut assets = AssetList::from(coins(40_000, DENOM));
+        assets.add(&Asset::cw20(
+            Addr::unchecked(cw20_addr.clone()),
+            Uint128::new(40),
+        ))?;
+        AssetListUnchecked::from(assets)
+    };
+    module_contract.create_task(
+        assets,
+        Box::new(task),
+        "test_tag2".to_owned(),
+        None,
+        None,
+        &[],
+    )?;
+
+    let creator_addr = account.manager.addr_str()?;
+    let task_tags = vec!["test_tag1".to_owned(), "test_tag2".to_owned()];
+
+    // Only admin can force remove tasks
+    module_contract.set_sender(&cw20_addr);
+    let err = module_contract.force_remove_tasks(creator_addr.clone(), task_tags.clone());
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        cw_controllers::AdminError::NotAdmin {}.to_string()
+    );
+    module_contract.set_sender(&account.manager.address()?);
+
+    let proxy_balance1 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    let proxy_cw20_balance1: cw20::BalanceResponse = mock.query(
+        &Cw20QueryMsg::Balance {
+            address: account.proxy.addr_str()?,
+        },
+        &cw20_addr,
+    )?;
+
+    module_contract.force_remove_tasks(creator_addr.clone(), task_tags)?;
+
+    // Everything landed on proxy contract
+    let proxy_balance2 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    assert_eq!(proxy_balance2, proxy_balance1 + Uint128::new(80_000));
+    let proxy_cw20_balance2: cw20::BalanceResponse = mock.query(
+        &Cw20QueryMsg::Balance {
+            address: account.proxy.addr_str()?,
+        },
+        &cw20_addr,
+    )?;
+    assert_eq!(
+        proxy_cw20_balance2.balance,
+        proxy_cw20_balance1.balance + Uint128::new(60)
+    );
+
+    // State updated
+    let active_tasks_by_creator_response: ActiveTasksByCreatorResponse =
+        module_contract.active_tasks_by_creator(creator_addr, None, None, None)?;
+    assert!(active_tasks_by_creator_response.unchecked().is_empty());
+    Ok(())
+}
+
+#[test]
+fn creator_filter() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        mut module_contract,
+        account,
+        ..
+    } = setup()?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+
+    // Deny croncat app itself, that is installed on the account
+    module_contract.update_config(
+        None,
+        Some(CreatorFilter::Deny(vec![CRONCAT_ID.to_owned()])),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )?;
+    module_contract.set_sender(&module_contract.address()?);
+    let err = module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        "test_tag".to_owned(),
+        None,
+        None,
+        &[],
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::CreatorNotAllowed {
+            module_id: CRONCAT_ID.to_owned()
+        }
+        .to_string()
+    );
+
+    // Allow it
+    module_contract.set_sender(&account.manager.address()?);
+    module_contract.update_config(
+        None,
+        Some(CreatorFilter::Allow(vec![CRONCAT_ID.to_owned()])),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )?;
+    module_contract.set_sender(&module_contract.address()?);
+    module_contract.create_task(
+        assets.clone(),
+        Box::new(task),
+        "test_tag".to_owned(),
+        None,
+        None,
+        &[],
+    )?;
+    module_contract.refill_task(assets.clone(), "test_tag".to_owned(), None)?;
+
+    // Not on the allowlist anymore
+    module_contract.set_sender(&account.manager.address()?);
+    module_contract.update_config(
+        None,
+        Some(CreatorFilter::Allow(vec!["abstract:dex".to_owned()])),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )?;
+    module_contract.set_sender(&module_contract.address()?);
+    let err = module_contract.refill_task(assets, "test_tag".to_owned(), None);
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::CreatorNotAllowed {
+            module_id: CRONCAT_ID.to_owned()
+        }
+        .to_string()
+    );
+
+    // Removal is still possible
+    module_contract.remove_task("test_tag".to_owned(), None)?;
+    Ok(())
+}
+
+#[test]
+fn action_policy() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract,
+        account,
+        cw20_addr,
+        ..
+    } = setup()?;
+
+    module_contract.update_config(
+        Some(ActionPolicy {
+            allowed_contracts: None,
+            allowed_msg_types: vec![ActionMsgType::Bank, ActionMsgType::Wasm],
+            max_native_amounts: Some(coins(500, DENOM)),
+            allow_account_calls: false,
+            allow_croncat_calls: false,
+        }),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )?;
+
+    let bank_task = |amount: u128| TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(amount, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+
+    // Over the limit
+    let err = module_contract.create_task(
+        assets.clone(),
+        Box::new(bank_task(600)),
+        "test_tag".to_owned(),
+        None,
+        None,
+        &[],
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::ActionNotAllowed {
+            action_idx: 0,
+            reason: format!("600{DENOM} exceeds maximum of 500{DENOM}")
+        }
+        .to_string()
+    );
+
+    // Calls to the account are not allowed
+    let proxy_task = TaskRequest {
+        actions: vec![Action {
+            msg: WasmMsg::Execute {
+                contract_addr: account.proxy.address()?.to_string(),
+                msg: to_binary(&"")?,
+                funds: vec![],
+            }
+            .into(),
+            gas_limit: Some(150_000),
+        }],
+        ..bank_task(0)
+    };
+    let err = module_contract.create_task(
+        assets.clone(),
+        Box::new(proxy_task),
+        "test_tag".to_owned(),
+        None,
+        None,
+        &[],
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::ActionNotAllowed {
+            action_idx: 0,
+            reason: format!("calls to the account {}", account.proxy.address()?)
+        }
+        .to_string()
+    );
+
+    // Messages croncat can't execute are refused even if wasm is allowed
+    let instantiate_task = TaskRequest {
+        actions: vec![Action {
+            msg: WasmMsg::Instantiate {
+                admin: None,
+                code_id: 1,
+                msg: to_binary(&"")?,
+                funds: vec![],
+                label: "label".to_owned(),
+            }
+            .into(),
+            gas_limit: Some(150_000),
+        }],
+        ..bank_task(0)
+    };
+    let err = module_contract.create_task(
+        assets.clone(),
+        Box::new(instantiate_task),
+        "test_tag".to_owned(),
+        None,
+        None,
+        &[],
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::ActionNotAllowed {
+            action_idx: 0,
+            reason: "unsupported message type".to_owned()
+        }
+        .to_string()
+    );
+
+    // Restrict to bank messages only
+    module_contract.update_config(
+        Some(ActionPolicy {
+            allowed_contracts: Some(vec![cw20_addr]),
+            allowed_msg_types: vec![ActionMsgType::Bank],
+            max_native_amounts: None,
+            allow_account_calls: false,
+            allow_croncat_calls: false,
+        }),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )?;
+    module_contract.create_task(
+        assets,
+        Box::new(bank_task(600)),
+        "test_tag".to_owned(),
+        None,
+        None,
+        &[],
+    )?;
+    Ok(())
+}
+
+#[test]
+fn spend_limit() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        mut module_contract,
+        mock,
+        ..
+    } = setup()?;
+
+    let day = 24 * 60 * 60;
+    module_contract.update_config(
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(SpendLimit {
+            assets: AssetList::from(coins(50_000, DENOM)),
+            period: day,
+        }),
+        None,
+        None,
+    )?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let task_tag = "test_tag".to_owned();
+
+    // Croncat app itself acts as the creator module
+    let creator_addr = module_contract.address()?;
+    module_contract.set_sender(&creator_addr);
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+    module_contract.create_task(assets, Box::new(task), task_tag.clone(), None, None, &[])?;
+
+    let allowance: SpendingAllowanceResponse =
+        module_contract.spending_allowance(creator_addr.to_string())?;
+    assert_eq!(
+        allowance,
+        SpendingAllowanceResponse {
+            window_start: Some(mock.block_info()?.time),
+            spent: AssetList::from(coins(45_000, DENOM)),
+            remaining: AssetList::from(coins(5_000, DENOM)),
+        }
+    );
+
+    // Over the limit
+    let assets = AssetListUnchecked::from(AssetList::from(coins(10_000, DENOM)));
+    let err = module_contract.refill_task(assets.clone(), task_tag.clone(), None);
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::SpendLimitExceeded {
+            remaining: Asset::native(DENOM, 5_000u128).to_string()
+        }
+        .to_string()
+    );
+
+    // Use the rest of the allowance half a day later
+    mock.wait_seconds(day / 2)?;
+    let refill_time = mock.block_info()?.time;
+    module_contract.refill_task(
+        AssetListUnchecked::from(AssetList::from(coins(5_000, DENOM))),
+        task_tag.clone(),
+        None,
+    )?;
+
+    // Window is rolling, only the first deposit is released after a day
+    mock.wait_seconds(day / 2)?;
+    let allowance: SpendingAllowanceResponse =
+        module_contract.spending_allowance(creator_addr.to_string())?;
+    assert_eq!(
+        allowance,
+        SpendingAllowanceResponse {
+            window_start: Some(refill_time),
+            spent: AssetList::from(coins(5_000, DENOM)),
+            remaining: AssetList::from(coins(45_000, DENOM)),
+        }
+    );
+    let err = module_contract.refill_task(
+        AssetListUnchecked::from(AssetList::from(coins(50_000, DENOM))),
+        task_tag.clone(),
+        None,
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::SpendLimitExceeded {
+            remaining: Asset::native(DENOM, 45_000u128).to_string()
+        }
+        .to_string()
+    );
+
+    module_contract.refill_task(assets, task_tag.clone(), None)?;
+    let task_balance: TaskBalanceResponse =
+        module_contract.task_balance(creator_addr.to_string(), task_tag.clone())?;
+    assert_eq!(
+        task_balance.balance.unwrap().native_balance,
+        Uint128::new(60_000)
+    );
+
+    // All deposits are released after a day since the last one
+    mock.wait_seconds(day)?;
+    let allowance: SpendingAllowanceResponse =
+        module_contract.spending_allowance(creator_addr.to_string())?;
+    assert_eq!(allowance.window_start, None);
+    assert_eq!(allowance.remaining, AssetList::from(coins(50_000, DENOM)));
+
+    // Oldest deposits get merged over the cap, nothing is released early
+    let mut deposit_times = vec![];
+    for _ in 0..=MAX_SPENDING_DEPOSITS {
+        mock.wait_seconds(60)?;
+        deposit_times.push(mock.block_info()?.time);
+        module_contract.refill_task(
+            AssetListUnchecked::from(AssetList::from(coins(100, DENOM))),
+            task_tag.clone(),
+            None,
+        )?;
+    }
+    let allowance: SpendingAllowanceResponse =
+        module_contract.spending_allowance(creator_addr.to_string())?;
+    assert_eq!(
+        allowance,
+        SpendingAllowanceResponse {
+            window_start: Some(deposit_times[1]),
+            spent: AssetList::from(coins(100 * (MAX_SPENDING_DEPOSITS as u128 + 1), DENOM)),
+            remaining: AssetList::from(coins(
+                50_000 - 100 * (MAX_SPENDING_DEPOSITS as u128 + 1),
+                DENOM
+            )),
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn reclaim_orphans() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        mut module_contract,
+        mock,
+        account,
+        cw20_addr,
+        ..
+    } = setup()?;
+
+    // Register cw20 contract as a module of the account
+    let cw20_module_id = "crates.io:cw20-base".to_owned();
+    account.manager.execute(
+        &abstract_core::manager::ExecuteMsg::UpdateInternalConfig(to_binary(
+            &InternalConfigAction::UpdateModuleAddresses {
+                to_add: Some(vec![(cw20_module_id.clone(), cw20_addr.to_string())]),
+                to_remove: None,
+            },
+        )?),
+        None,
+    )?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+    module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        "admin_tag".to_owned(),
+        None,
+        None,
+        &[],
+    )?;
+    // Same task would have the same hash on croncat
+    let task = TaskRequest {
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "bob".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        ..task
+    };
+    module_contract.set_sender(&cw20_addr);
+    module_contract.create_task(
+        assets,
+        Box::new(task),
+        "module_tag".to_owned(),
+        None,
+        None,
+        &[],
+    )?;
+
+    // Uninstall the module
+    account.manager.execute(
+        &abstract_core::manager::ExecuteMsg::UpdateInternalConfig(to_binary(
+            &InternalConfigAction::UpdateModuleAddresses {
+                to_add: None,
+                to_remove: Some(vec![cw20_module_id]),
+            },
+        )?),
+        None,
+    )?;
+
+    // Only admin can reclaim orphans
+    let err = module_contract.reclaim_orphans(None, None);
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        cw_controllers::AdminError::NotAdmin {}.to_string()
+    );
+    module_contract.set_sender(&account.manager.address()?);
+
+    let proxy_balance1 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    // Nothing after the orphan task to check
+    module_contract.reclaim_orphans(
+        Some(1),
+        Some((cw20_addr.to_string(), "module_tag".to_owned())),
+    )?;
+    assert_eq!(
+        mock.query_balance(&account.proxy.address()?, DENOM)?,
+        proxy_balance1
+    );
+    module_contract.reclaim_orphans(None, None)?;
+
+    // Funds of the orphan task landed on proxy contract
+    let proxy_balance2 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    assert_eq!(proxy_balance2, proxy_balance1 + Uint128::new(45_000));
+
+    // Only task of the admin left
+    let active_tasks_response: ActiveTasksResponse =
+        module_contract.active_tasks(Some(true), None, None)?;
+    let (scheduled_tasks, removed_tasks) = active_tasks_response.checked();
+    assert_eq!(
+        scheduled_tasks,
+        vec![(account.manager.address()?, "admin_tag".to_owned())]
+    );
+    assert!(removed_tasks.is_empty());
+
+    // Orphan task is archived
+    let archived_tasks_response: ArchivedTasksResponse =
+        module_contract.archived_tasks(None, None)?;
+    let archived_tasks = archived_tasks_response.tasks;
+    assert_eq!(archived_tasks.len(), 1);
+    assert_eq!(
+        archived_tasks[0].0,
+        (cw20_addr.clone(), "module_tag".to_owned())
+    );
+
+    // Nothing left to reclaim
+    module_contract.reclaim_orphans(None, None)?;
+    let proxy_balance3 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    assert_eq!(proxy_balance3, proxy_balance2);
+    Ok(())
+}
+
+#[test]
+fn croncat_errors() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract,
+        abstr_deployment,
+        mock,
+        account,
+        ..
+    } = setup()?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let task_tag = "test_tag".to_owned();
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+    module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        task_tag.clone(),
+        None,
+        None,
+        &[],
+    )?;
+
+    // Not enough balance for recurring task
+    let recurring_task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Block(5),
+        ..task
+    };
+    let err = module_contract.create_task(
+        AssetListUnchecked::from(AssetList::from(coins(1_000, DENOM))),
+        Box::new(recurring_task),
+        "recurring_tag".to_owned(),
+        None,
+        None,
+        &[],
+    );
+    // CronCat error aborts the transaction as is
+    assert!(err
+        .unwrap_err()
+        .root()
+        .to_string()
+        .starts_with("Not enough native balance"));
+
+    let contracts_response: ContractsResponse =
+        abstr_deployment
+            .ans_host
+            .query(&abstract_core::ans_host::QueryMsg::Contracts {
+                entries: vec![
+                    UncheckedContractEntry::try_from(CRON_CAT_FACTORY.to_owned())?.into(),
+                ],
+            })?;
+    let factory_addr: Addr = contracts_response.contracts[0].1.clone();
+    let response: ContractMetadataResponse = mock.query(
+        &FactoryQueryMsg::LatestContract {
+            contract_name: TASKS_NAME.to_string(),
+        },
+        &factory_addr,
+    )?;
+    let tasks_addr: Addr = response.metadata.unwrap().contract_addr;
+    let manager_addr: Addr =
+        module_contract.manager_addr(account.manager.addr_str()?, task_tag.clone())?;
+
+    // Paused croncat contracts
+    mock.app.borrow_mut().execute_contract(
+        Addr::unchecked(PAUSE_ADMIN),
+        tasks_addr,
+        &croncat_sdk_tasks::msg::TasksExecuteMsg::PauseContract {},
+        &[],
+    )?;
+    mock.app.borrow_mut().execute_contract(
+        Addr::unchecked(PAUSE_ADMIN),
+        manager_addr,
+        &ManagerExecuteMsg::PauseContract {},
+        &[],
+    )?;
+
+    let err = module_contract.refill_task(assets, task_tag.clone(), None);
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        croncat_manager::ContractError::ContractPaused.to_string()
+    );
+
+    let err = module_contract.remove_task(task_tag.clone(), None);
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        croncat_tasks::ContractError::ContractPaused.to_string()
+    );
+
+    // Task stays active
+    let active_tasks_by_creator_response: ActiveTasksByCreatorResponse =
+        module_contract.active_tasks_by_creator(account.manager.addr_str()?, None, None, None)?;
+    assert_eq!(active_tasks_by_creator_response.unchecked(), vec![task_tag]);
+    Ok(())
+}
+
+#[test]
+fn pause() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        mut module_contract,
+        account,
+        ..
+    } = setup()?;
+
+    let pauser = Addr::unchecked("pauser");
+    module_contract.update_config(
+        None,
+        None,
+        None,
+        None,
+        Some(pauser.to_string()),
+        None,
+        None,
+        None,
+    )?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let task_tag = "test_tag".to_owned();
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+    module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        task_tag.clone(),
+        None,
+        None,
+        &[],
+    )?;
+
+    // Only admin or pauser can pause
+    module_contract.set_sender(&Addr::unchecked("bob"));
+    let err = module_contract.pause();
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        cw_controllers::AdminError::NotAdmin {}.to_string()
+    );
+    module_contract.set_sender(&pauser);
+    module_contract.pause()?;
+    let config_res: ConfigResponse = module_contract.config()?;
+    assert!(config_res.config.paused);
+
+    // Creation and refills are blocked
+    module_contract.set_sender(&account.manager.address()?);
+    let err = module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        "new_tag".to_owned(),
+        None,
+        None,
+        &[],
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::Paused {}.to_string()
+    );
+    let err = module_contract.refill_task(assets.clone(), task_tag.clone(), None);
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::Paused {}.to_string()
+    );
+
+    // Update of the config keeps paused state
+    module_contract.update_config(
+        None,
+        None,
+        None,
+        None,
+        Some(pauser.to_string()),
+        None,
+        None,
+        None,
+    )?;
+    let config_res: ConfigResponse = module_contract.config()?;
+    assert!(config_res.config.paused);
+
+    // Only admin can unpause
+    module_contract.set_sender(&pauser);
+    let err = module_contract.unpause();
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        cw_controllers::AdminError::NotAdmin {}.to_string()
+    );
+    module_contract.set_sender(&account.manager.address()?);
+
+    // Removal still works
+    module_contract.remove_task(task_tag.clone(), None)?;
+
+    module_contract.unpause()?;
+    module_contract.create_task(assets, Box::new(task), task_tag, None, None, &[])?;
+    Ok(())
+}
+
+// Overwrite contract storage of the multi-test app with the raw state
+fn set_contract_state(mock: &Mock, contract_addr: &Addr, state: &MockStorage) {
+    // Multi-test nests contract storage under "wasm" and "contract_data/{addr}" namespaces
+    let mut prefix = vec![];
+    for namespace in [
+        b"wasm".to_vec(),
+        [b"contract_data/", contract_addr.as_bytes()].concat(),
+    ] {
+        prefix.extend_from_slice(&(namespace.len() as u16).to_be_bytes());
+        prefix.extend_from_slice(&namespace);
+    }
+    // Abstract base state is kept, everything else is replaced
+    let base_keys: [&[u8]; 3] = [b"base_state", b"admin", b"module_data"];
+    mock.app
+        .borrow_mut()
+        .init_modules(|_router, _api, storage| {
+            let app_keys: Vec<Vec<u8>> = storage
+                .range(Some(&prefix), None, Order::Ascending)
+                .map(|(key, _)| key)
+                .take_while(|key| key.starts_with(&prefix))
+                .filter(|key| {
+                    !base_keys
+                        .iter()
+                        .any(|base_key| key[prefix.len()..].starts_with(base_key))
+                })
+                .collect();
+            for key in app_keys {
+                storage.remove(&key);
+            }
+            for (key, value) in state.range(None, None, Order::Ascending) {
+                storage.set(&[prefix.as_slice(), &key].concat(), &value);
+            }
+        });
+}
+
+#[test]
+fn migrate_from_0_0_1() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract,
+        mock,
+        account,
+        ..
+    } = setup()?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+    module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        "tag1".to_owned(),
+        None,
+        None,
+        &[],
+    )?;
+    let task = TaskRequest {
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "bob".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        ..task
+    };
+    module_contract.create_task(assets, Box::new(task), "tag2".to_owned(), None, None, &[])?;
+
+    // Write state of 0.0.1 version
+    #[cosmwasm_schema::cw_serde]
+    struct ConfigV0_0_1 {}
+    let active_tasks_v0_0_1: Map<(Addr, String), (String, String)> = Map::new("active_tasks");
+
+    let creator_addr = account.manager.address()?;
+    let mut state = MockStorage::new();
+    Item::new("config").save(&mut state, &ConfigV0_0_1 {})?;
+    for task_tag in ["tag1", "tag2"] {
+        let task_response: TaskResponse =
+            module_contract.task_info(creator_addr.to_string(), task_tag.to_owned())?;
+        let task = task_response.task.unwrap();
+        active_tasks_v0_0_1.save(
+            &mut state,
+            (creator_addr.clone(), task_tag.to_owned()),
+            &(task.task_hash, task.version),
+        )?;
+    }
+    cw2::set_contract_version(&mut state, CRONCAT_ID, "0.0.1")?;
+    let app_addr = module_contract.address()?;
+    let contract_info = mock
+        .app
+        .borrow()
+        .wrap()
+        .query_wasm_contract_info(&app_addr)?;
+    let migrate = || {
+        mock.app.borrow_mut().migrate_contract(
+            Addr::unchecked(contract_info.admin.clone().unwrap()),
+            app_addr.clone(),
+            &MigrateMsg {
+                base: BaseMigrateMsg {},
+                module: AppMigrateMsg {},
+            },
+            contract_info.code_id,
+        )
+    };
+
+    // Migration fails if the old config is not there
+    state.remove(b"config");
+    set_contract_state(&mock, &app_addr, &state);
+    assert!(migrate().is_err());
+
+    Item::new("config").save(&mut state, &ConfigV0_0_1 {})?;
+    set_contract_state(&mock, &app_addr, &state);
+
+    // Current version can't read old state
+    assert!(module_contract.config().is_err());
+    assert!(module_contract
+        .task_info(creator_addr.to_string(), "tag1".to_owned())
+        .is_err());
+
+    let migrate_res = migrate()?;
+    assert!(migrate_res.has_event(
+        &Event::new("wasm")
+            .add_attribute("from_state_version", "0")
+            .add_attribute("to_state_version", "2")
+    ));
+
+    let config_res: ConfigResponse = module_contract.config()?;
+    assert_eq!(config_res.config, Config::default());
+
+    // Tasks are usable again
+    let active_tasks_response: ActiveTasksResponse =
+        module_contract.active_tasks(Some(true), None, None)?;
+    let (scheduled_tasks, removed_tasks) = active_tasks_response.checked();
+    assert_eq!(scheduled_tasks.len(), 2);
+    assert!(removed_tasks.is_empty());
+
+    // Tasks are indexed by hash
+    let task_response: TaskResponse =
+        module_contract.task_info(creator_addr.to_string(), "tag1".to_owned())?;
+    let task_hash = task_response.task.unwrap().task_hash;
+    let err = module_contract.adopt_task(task_hash.clone(), "tag3".to_owned());
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::TaskAlreadyRegistered {
+            task_hash,
+            task_tag: "tag1".to_owned()
+        }
+        .to_string()
+    );
+
+    let proxy_balance1 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    module_contract.remove_task("tag1".to_owned(), None)?;
+    module_contract.remove_task("tag2".to_owned(), None)?;
+    let proxy_balance2 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    assert_eq!(proxy_balance2, proxy_balance1 + Uint128::new(90_000));
+    Ok(())
+}
+
+#[test]
+fn pinned_croncat_version() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract,
+        account,
+        ..
+    } = setup()?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+
+    // Version that is not deployed
+    module_contract.update_config(None, None, Some([1, 1]), None, None, None, None, None)?;
+    let err = module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        "test_tag".to_owned(),
+        None,
+        None,
+        &[],
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::PinnedVersionNotFound {
+            version: "1.1".to_owned()
+        }
+        .to_string()
+    );
+
+    module_contract.update_config(None, None, Some([1, 0]), None, None, None, None, None)?;
+    module_contract.create_task(
+        assets,
+        Box::new(task),
+        "test_tag".to_owned(),
+        None,
+        None,
+        &[],
+    )?;
+    let task_response: TaskResponse =
+        module_contract.task_info(account.manager.addr_str()?, "test_tag".to_owned())?;
+    assert_eq!(task_response.task.unwrap().version, "1.0");
+    Ok(())
+}
+
+#[test]
+fn adopt_task() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract,
+        abstr_deployment,
+        mock,
+        account,
+        ..
+    } = setup()?;
+
+    let contracts_response: ContractsResponse =
+        abstr_deployment
+            .ans_host
+            .query(&abstract_core::ans_host::QueryMsg::Contracts {
+                entries: vec![
+                    UncheckedContractEntry::try_from(CRON_CAT_FACTORY.to_owned())?.into(),
+                ],
+            })?;
+    let factory_addr: Addr = contracts_response.contracts[0].1.clone();
+    let response: ContractMetadataResponse = mock.query(
+        &FactoryQueryMsg::LatestContract {
+            contract_name: TASKS_NAME.to_string(),
+        },
+        &factory_addr,
+    )?;
+    let tasks_addr: Addr = response.metadata.unwrap().contract_addr;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let create_task = |sender: &Addr, task: TaskRequest| -> anyhow::Result<String> {
+        let res = mock.app.borrow_mut().execute_contract(
+            sender.clone(),
+            tasks_addr.clone(),
+            &croncat_sdk_tasks::msg::TasksExecuteMsg::CreateTask {
+                task: Box::new(task),
+            },
+            &coins(45_000, DENOM),
+        )?;
+        let task_hash = res
+            .events
+            .iter()
+            .flat_map(|event| &event.attributes)
+            .find(|attr| attr.key == "task_hash")
+            .unwrap()
+            .value
+            .clone();
+        Ok(task_hash)
+    };
+
+    // Task created directly by the proxy
+    let task_hash = create_task(&account.proxy.address()?, task.clone())?;
+    // Task of someone else
+    let agent_task_hash = create_task(&Addr::unchecked(AGENT), task)?;
+
+    let err = module_contract.adopt_task(agent_task_hash.clone(), "agent_tag".to_owned());
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::NotTaskOwner {
+            task_hash: agent_task_hash
+        }
+        .to_string()
+    );
+    let err = module_contract.adopt_task("atom:unknown".to_owned(), "unknown_tag".to_owned());
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::TaskNotFound {
+            task_tag: "unknown_tag".to_owned()
+        }
+        .to_string()
+    );
+
+    let task_tag = "adopted_tag".to_owned();
+    module_contract.adopt_task(task_hash.clone(), task_tag.clone())?;
+    let err = module_contract.adopt_task(task_hash.clone(), "another_tag".to_owned());
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::TaskAlreadyRegistered {
+            task_hash: task_hash.clone(),
+            task_tag: task_tag.clone()
+        }
+        .to_string()
+    );
+
+    // Works like any other task
+    let task_response: TaskResponse =
+        module_contract.task_info(account.manager.addr_str()?, task_tag.clone())?;
+    assert_eq!(task_response.task.unwrap().task_hash, task_hash);
+    let assets = AssetListUnchecked::from(AssetList::from(coins(1_000, DENOM)));
+    module_contract.refill_task(assets, task_tag.clone(), None)?;
+
+    let proxy_balance1 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    module_contract.remove_task(task_tag, None)?;
+    let proxy_balance2 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    assert_eq!(proxy_balance2, proxy_balance1 + Uint128::new(46_000));
+    Ok(())
+}
+
+#[test]
+fn creation_modes() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract,
+        mock,
+        account,
+        abstr_deployment,
+        ..
+    } = setup()?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let task_tag = "test_tag".to_owned();
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+    let res = module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        task_tag.clone(),
+        None,
+        None,
+        &[],
+    )?;
+    let created_info: CronCatTaskExecutionInfo = from_binary(&res.data.unwrap())?;
+    let task_response: TaskResponse =
+        module_contract.task_info(account.manager.addr_str()?, task_tag.clone())?;
+    let task_hash = task_response.task.unwrap().task_hash;
+
+    let err = module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        task_tag.clone(),
+        None,
+        Some(CreationMode::Fail),
+        &[],
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::TaskAlreadyExists {
+            task_tag: task_tag.clone()
+        }
+        .to_string()
+    );
+
+    // Existing task is returned without changes
+    let proxy_balance1 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    let res = module_contract.create_task(
+        assets.clone(),
+        Box::new(task.clone()),
+        task_tag.clone(),
+        None,
+        Some(CreationMode::ReturnExisting),
+        &[],
+    )?;
+    let returned_info: CronCatTaskExecutionInfo = from_binary(&res.data.unwrap())?;
+    assert_eq!(returned_info.task_hash, task_hash);
+    assert_eq!(
+        returned_info,
+        CronCatTaskExecutionInfo {
+            tx_info: None,
+            ..created_info
+        }
+    );
+    let proxy_balance2 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    assert_eq!(proxy_balance2, proxy_balance1);
+
+    // Replaced task gets new hash and funds of the old one are withdrawn
+    let task = TaskRequest {
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "bob".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        ..task
+    };
+    let assets = AssetListUnchecked::from(AssetList::from(coins(50_000, DENOM)));
+    module_contract.create_task(
+        assets,
+        Box::new(task.clone()),
+        task_tag.clone(),
+        None,
+        Some(CreationMode::Replace),
+        &[],
+    )?;
+    let proxy_balance3 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    assert_eq!(proxy_balance3, proxy_balance2 - Uint128::new(5_000));
+
+    let task_response: TaskResponse =
+        module_contract.task_info(account.manager.addr_str()?, task_tag.clone())?;
+    let new_task_hash = task_response.task.unwrap().task_hash;
+    assert_ne!(new_task_hash, task_hash);
+    let active_tasks_by_creator_response: ActiveTasksByCreatorResponse =
+        module_contract.active_tasks_by_creator(account.manager.addr_str()?, None, None, None)?;
+    let active_tasks_by_creator = active_tasks_by_creator_response.unchecked();
+    assert_eq!(active_tasks_by_creator, vec![task_tag.clone()]);
+
+    // Agent executes the task, so it's not on croncat anymore
+    {
+        mock.wait_blocks(3)?;
+        let contracts_response: ContractsResponse =
+            abstr_deployment
+                .ans_host
+                .query(&abstract_core::ans_host::QueryMsg::Contracts {
+                    entries: vec![
+                        UncheckedContractEntry::try_from(CRON_CAT_FACTORY.to_owned())?.into(),
+                    ],
+                })?;
+        let factory_addr: Addr = contracts_response.contracts[0].1.clone();
+        let response: ContractMetadataResponse = mock.query(
+            &FactoryQueryMsg::LatestContract {
+                contract_name: MANAGER_NAME.to_string(),
+            },
+            &factory_addr,
+        )?;
+        let manager_addr: Addr = response.metadata.unwrap().contract_addr;
+        mock.app.borrow_mut().execute_contract(
+            Addr::unchecked(AGENT),
+            manager_addr,
+            &ManagerExecuteMsg::ProxyCall { task_hash: None },
+            &[],
+        )?;
+    };
+    let task_response: TaskResponse =
+        module_contract.task_info(account.manager.addr_str()?, task_tag.clone())?;
+    assert!(task_response.task.is_none());
+
+    // Stale task is cleared and the new one is created
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+    let res = module_contract.create_task(
+        assets,
+        Box::new(task),
+        task_tag.clone(),
+        None,
+        Some(CreationMode::ReturnExisting),
+        &[],
+    )?;
+    let recreated_info: CronCatTaskExecutionInfo = from_binary(&res.data.unwrap())?;
+    assert!(recreated_info.tx_info.is_some());
+    let task_response: TaskResponse =
+        module_contract.task_info(account.manager.addr_str()?, task_tag)?;
+    assert_eq!(
+        task_response.task.unwrap().task_hash,
+        recreated_info.task_hash
+    );
+    Ok(())
+}
+
+#[test]
+fn task_tag_prefixes() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract,
+        account,
+        ..
+    } = setup()?;
+
+    let task = |recipient: &str| TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: recipient.to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+
+    module_contract.update_config(
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(TagRules {
+            max_len: 16,
+            allowed_chars: "_-".to_owned(),
+        }),
+        None,
+    )?;
+
+    for (task_tag, reason) in [
+        ("vault1//harvest", "empty segment"),
+        ("vault1/", "empty segment"),
+        ("vault 1", "character ' ' is not allowed"),
+        ("vault1/compound_all", "longer than 16 bytes"),
+    ] {
+        let err = module_contract.create_task(
+            assets.clone(),
+            Box::new(task("alice")),
+            task_tag.to_owned(),
+            None,
+            None,
+            &[],
+        );
+        assert_eq!(
+            err.unwrap_err().root().to_string(),
+            AppError::InvalidTaskTag {
+                task_tag: task_tag.to_owned(),
+                reason: reason.to_owned()
+            }
+            .to_string()
+        );
+    }
+
+    for (task_tag, recipient) in [
+        ("vault1/compound", "alice"),
+        ("vault1/harvest", "bob"),
+        ("vault1", "carol"),
+        ("vault10/compound", "dave"),
+    ] {
+        module_contract.create_task(
+            assets.clone(),
+            Box::new(task(recipient)),
+            task_tag.to_owned(),
+            None,
+            None,
+            &[],
+        )?;
+    }
+
+    let response: ActiveTasksByCreatorResponse = module_contract.active_tasks_by_prefix(
+        account.manager.addr_str()?,
+        "vault1".to_owned(),
+        None,
+        None,
+        None,
+    )?;
+    assert_eq!(
+        response.unchecked(),
+        vec!["vault1/compound".to_owned(), "vault1/harvest".to_owned()]
+    );
+
+    // Paginated
+    let response: ActiveTasksByCreatorResponse = module_contract.active_tasks_by_prefix(
+        account.manager.addr_str()?,
+        "vault1".to_owned(),
+        None,
+        Some(1),
+        None,
+    )?;
+    assert_eq!(response.unchecked(), vec!["vault1/compound".to_owned()]);
+    let response: ActiveTasksByCreatorResponse = module_contract.active_tasks_by_prefix(
+        account.manager.addr_str()?,
+        "vault1".to_owned(),
+        None,
+        None,
+        Some("vault1/compound".to_owned()),
+    )?;
+    assert_eq!(response.unchecked(), vec!["vault1/harvest".to_owned()]);
+    Ok(())
+}
+
+#[test]
+fn dca_task() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract,
+        account,
+        ..
+    } = setup()?;
+
+    module_contract.update_config(
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(TagRules {
+            max_len: 64,
+            allowed_chars: "".to_owned(),
+        }),
+        None,
+    )?;
+
+    // DCA task is accepted by croncat and its tag passes the strictest rules
+    let offer_asset = AnsAsset::new("juno>juno", 100u128);
+    let ask_asset = AssetEntry::new("juno>atom");
+    let task_tag = dca_task_tag(&offer_asset.name, &ask_asset, "junoswap");
+    let task = dca_task_request(
+        Addr::unchecked("dex_adapter"),
+        account.proxy.address()?,
+        offer_asset,
+        ask_asset,
+        croncat_sdk_tasks::types::Interval::Block(10),
+        "junoswap".to_owned(),
+        200_000,
+    )?;
+    module_contract.create_task(
+        AssetListUnchecked::from(AssetList::from(coins(500_000, DENOM))),
+        Box::new(task.clone()),
+        task_tag.clone(),
+        None,
+        None,
+        &[],
+    )?;
+    let task_response: TaskResponse =
+        module_contract.task_info(account.manager.addr_str()?, task_tag.clone())?;
+    assert_eq!(task_response.task.unwrap().actions, task.actions);
+
+    let response: ActiveTasksByCreatorResponse = module_contract.active_tasks_by_prefix(
+        account.manager.addr_str()?,
+        "dca/junoswap".to_owned(),
+        None,
+        None,
+        None,
+    )?;
+    assert_eq!(response.unchecked(), vec![task_tag]);
+    Ok(())
+}
+
+#[test]
+fn task_groups() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract,
+        account,
+        ..
+    } = setup()?;
+
+    let task = |recipient: &str| TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: recipient.to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+    let group = "vault1".to_owned();
+    let creator_addr = account.manager.addr_str()?;
+
+    module_contract.create_task(
+        assets.clone(),
+        Box::new(task("alice")),
+        "harvest".to_owned(),
+        Some(group.clone()),
+        None,
+        &[],
+    )?;
+    module_contract.create_task(
+        assets.clone(),
+        Box::new(task("bob")),
+        "compound".to_owned(),
+        Some(group.clone()),
+        None,
+        &[],
+    )?;
+    module_contract.create_task(
+        assets,
+        Box::new(task("carol")),
+        "rebalance".to_owned(),
+        None,
+        None,
+        &[],
+    )?;
+
+    let group_tasks_response: GroupTasksResponse =
+        module_contract.group_tasks(creator_addr.clone(), group.clone(), None, None)?;
+    assert_eq!(group_tasks_response.tasks, vec!["compound", "harvest"]);
+
+    let native_balance = |task_tag: &str| -> anyhow::Result<Uint128> {
+        let task_balance: TaskBalanceResponse =
+            module_contract.task_balance(creator_addr.clone(), task_tag.to_owned())?;
+        Ok(task_balance.balance.unwrap().native_balance)
+    };
+
+    // Even split
+    let assets = AssetListUnchecked::from(AssetList::from(coins(1_001, DENOM)));
+    module_contract.refill_group(assets, group.clone(), GroupSplit::Even)?;
+    assert_eq!(native_balance("harvest")?, Uint128::new(45_500));
+    assert_eq!(native_balance("compound")?, Uint128::new(45_500));
+    assert_eq!(native_balance("rebalance")?, Uint128::new(45_000));
+
+    // Weighted split
+    let assets = AssetListUnchecked::from(AssetList::from(coins(400, DENOM)));
+    module_contract.refill_group(
+        assets.clone(),
+        group.clone(),
+        GroupSplit::Weighted(vec![("harvest".to_owned(), 3), ("compound".to_owned(), 1)]),
+    )?;
+    assert_eq!(native_balance("harvest")?, Uint128::new(45_800));
+    assert_eq!(native_balance("compound")?, Uint128::new(45_600));
+
+    let err = module_contract.refill_group(
+        assets.clone(),
+        group.clone(),
+        GroupSplit::Weighted(vec![("rebalance".to_owned(), 1)]),
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::InvalidGroupSplit {
+            reason: "rebalance is not in the group vault1".to_owned()
+        }
+        .to_string()
+    );
+
+    // Removing the group keeps other tasks
+    module_contract.remove_group(group.clone())?;
+    let active_tasks_by_creator_response: ActiveTasksByCreatorResponse =
+        module_contract.active_tasks_by_creator(creator_addr.clone(), None, None, None)?;
+    assert_eq!(
+        active_tasks_by_creator_response.unchecked(),
+        vec!["rebalance".to_owned()]
+    );
+    let group_tasks_response: GroupTasksResponse =
+        module_contract.group_tasks(creator_addr, group.clone(), None, None)?;
+    assert!(group_tasks_response.tasks.is_empty());
+
+    let err = module_contract.refill_group(assets, group.clone(), GroupSplit::Even);
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::GroupNotFound { group }.to_string()
+    );
+    Ok(())
+}
+
+#[test]
+fn task_templates() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract,
+        account,
+        ..
+    } = setup()?;
+
+    let template = Binary::from(
+        br#"{
+            "interval": { "block": {{blocks}} },
+            "stop_on_fail": false,
+            "actions": [{
+                "msg": {
+                    "bank": {
+                        "send": {
+                            "to_address": "{{recipient}}",
+                            "amount": [{ "denom": "abstr", "amount": "{{amount}}" }]
+                        }
+                    }
+                }
+            }]
+        }"#
+        .as_slice(),
+    );
+    let assets = AssetListUnchecked::from(AssetList::from(coins(60_000, DENOM)));
+    let err =
+        module_contract.register_template("payment".to_owned(), Binary::from(br#"[]"#.as_slice()));
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::InvalidTemplate {
+            reason: "task must be a json object".to_owned()
+        }
+        .to_string()
+    );
+    module_contract.register_template("payment".to_owned(), template.clone())?;
+
+    let err = module_contract.register_template("payment".to_owned(), template);
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::TemplateAlreadyExists {
+            name: "payment".to_owned()
+        }
+        .to_string()
+    );
+
+    let err = module_contract.create_task_from_template(
+        assets.clone(),
+        vec![],
+        "alice_payment".to_owned(),
+        "payment".to_owned(),
+        &[],
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::InvalidTemplateParams {
+            reason: "missing param blocks".to_owned()
+        }
+        .to_string()
+    );
+    let err = module_contract.create_task_from_template(
+        assets.clone(),
+        vec![
+            ("blocks".to_owned(), "10".to_owned()),
+            ("recipient".to_owned(), "alice".to_owned()),
+            ("amount".to_owned(), "420".to_owned()),
+            ("memo".to_owned(), "1".to_owned()),
+        ],
+        "alice_payment".to_owned(),
+        "payment".to_owned(),
+        &[],
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::InvalidTemplateParams {
+            reason: "unknown param memo".to_owned()
+        }
+        .to_string()
+    );
+
+    let err = module_contract.create_task_from_template(
+        assets.clone(),
+        vec![
+            ("blocks".to_owned(), "ten".to_owned()),
+            ("recipient".to_owned(), "alice".to_owned()),
+            ("amount".to_owned(), "420".to_owned()),
+        ],
+        "alice_payment".to_owned(),
+        "payment".to_owned(),
+        &[],
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::InvalidTemplateParams {
+            reason: "param blocks must be a number or a boolean".to_owned()
+        }
+        .to_string()
+    );
+
+    let params = vec![
+        ("blocks".to_owned(), "10".to_owned()),
+        ("recipient".to_owned(), "alice".to_owned()),
+        ("amount".to_owned(), "420".to_owned()),
+    ];
+    module_contract.create_task_from_template(
+        assets,
+        params.clone(),
+        "alice_payment".to_owned(),
+        "payment".to_owned(),
+        &[],
+    )?;
+    let task_response: TaskResponse =
+        module_contract.task_info(account.manager.addr_str()?, "alice_payment".to_owned())?;
+    let task = task_response.task.unwrap();
+    assert_eq!(task.interval, croncat_sdk_tasks::types::Interval::Block(10));
+    assert_eq!(
+        task.actions[0].msg,
+        BankMsg::Send {
+            to_address: "alice".to_owned(),
+            amount: coins(420, DENOM),
+        }
+        .into()
+    );
+    let task_template: Option<TemplateInstance> =
+        module_contract.task_template(account.manager.addr_str()?, "alice_payment".to_owned())?;
+    assert_eq!(
+        task_template,
+        Some(TemplateInstance {
+            template: "payment".to_owned(),
+            params
+        })
+    );
+
+    // Template record is removed with the task
+    module_contract.remove_task("alice_payment".to_owned(), None)?;
+    let task_template: Option<TemplateInstance> =
+        module_contract.task_template(account.manager.addr_str()?, "alice_payment".to_owned())?;
+    assert!(task_template.is_none());
+
+    module_contract.remove_template("payment".to_owned())?;
+    let template: Result<Template, _> = module_contract.template("payment".to_owned());
+    assert!(template.is_err());
+    Ok(())
+}
+
+#[test]
+fn task_meta() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        mut module_contract,
+        mock,
+        ..
+    } = setup()?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let assets = AssetList::from(coins(45_000, DENOM));
+
+    // App itself acts as the installed creator module
+    let creator_addr = module_contract.address()?;
+    module_contract.set_sender(&creator_addr);
+    module_contract.create_task(
+        AssetListUnchecked::from(assets.clone()),
+        Box::new(task),
+        "test_tag".to_owned(),
+        None,
+        None,
+        &[],
+    )?;
+
+    let block_info = mock.block_info()?;
+    let task_meta: Option<TaskMeta> =
+        module_contract.task_meta(creator_addr.to_string(), "test_tag".to_owned())?;
+    let task_meta = task_meta.unwrap();
+    assert_eq!(task_meta.created_at_height, block_info.height);
+    assert_eq!(task_meta.created_at, block_info.time);
+    assert_eq!(task_meta.creator_module, Some(CRONCAT_ID.to_owned()));
+    assert_eq!(task_meta.initial_deposit, assets);
+    let manager_addr: Addr =
+        module_contract.manager_addr(creator_addr.to_string(), "test_tag".to_owned())?;
+    assert_eq!(task_meta.manager_addr, manager_addr);
+
+    // Metadata is removed with the task
+    module_contract.remove_task("test_tag".to_owned(), None)?;
+    let task_meta: Option<TaskMeta> =
+        module_contract.task_meta(creator_addr.to_string(), "test_tag".to_owned())?;
+    assert!(task_meta.is_none());
+    Ok(())
+}
+
+#[test]
+fn task_cost() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        module_contract,
+        account,
+        ..
+    } = setup()?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let task_tag = "test_tag".to_owned();
+    let creator_addr = account.manager.addr_str()?;
+    module_contract.create_task(
+        AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM))),
+        Box::new(task.clone()),
+        task_tag.clone(),
+        None,
+        None,
+        &[],
+    )?;
+    module_contract.refill_task(
+        AssetListUnchecked::from(AssetList::from(coins(1_000, DENOM))),
+        task_tag.clone(),
+        None,
+    )?;
+
+    let task_cost: TaskCostResponse =
+        module_contract.task_cost(creator_addr.clone(), task_tag.clone())?;
+    assert_eq!(
+        task_cost,
+        TaskCostResponse {
+            deposited: AssetList::from(coins(46_000, DENOM)),
+            withdrawn: AssetList::new(),
+            cap: None,
+            balance: Some(AssetList::from(coins(46_000, DENOM))),
+            spent: AssetList::from(coins(0, DENOM)),
+        }
+    );
+
+    // Ledger is kept after removal
+    module_contract.remove_task(task_tag.clone(), None)?;
+    let task_cost: TaskCostResponse =
+        module_contract.task_cost(creator_addr.clone(), task_tag.clone())?;
+    assert_eq!(
+        task_cost,
+        TaskCostResponse {
+            deposited: AssetList::from(coins(46_000, DENOM)),
+            withdrawn: AssetList::from(coins(46_000, DENOM)),
+            cap: None,
+            balance: None,
+            spent: AssetList::from(coins(0, DENOM)),
+        }
+    );
+
+    // And reset when the tag is reused
+    module_contract.create_task(
+        AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM))),
+        Box::new(task),
+        task_tag.clone(),
+        None,
+        None,
+        &[],
+    )?;
+    let task_cost: TaskCostResponse = module_contract.task_cost(creator_addr, task_tag)?;
+    assert_eq!(task_cost.deposited, AssetList::from(coins(45_000, DENOM)));
+    assert!(task_cost.withdrawn.is_empty());
+    Ok(())
+}
+
+#[test]
+fn budget_caps() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        mut module_contract,
+        mock,
+        account,
+        ..
+    } = setup()?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let task_tag = "test_tag".to_owned();
+    let creator_addr = account.manager.addr_str()?;
+    module_contract.create_task(
+        AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM))),
+        Box::new(task),
+        task_tag.clone(),
+        None,
+        None,
+        &[],
+    )?;
+    let cap = AssetList::from(coins(46_000, DENOM));
+    module_contract.set_budget(
+        task_tag.clone(),
+        Some(AssetListUnchecked::from(cap.clone())),
+    )?;
+    let task_cost: TaskCostResponse =
+        module_contract.task_cost(creator_addr.clone(), task_tag.clone())?;
+    assert_eq!(task_cost.cap, Some(cap));
+
+    module_contract.refill_task(
+        AssetListUnchecked::from(AssetList::from(coins(1_000, DENOM))),
+        task_tag.clone(),
+        None,
+    )?;
+    let err = module_contract.refill_task(
+        AssetListUnchecked::from(AssetList::from(coins(1, DENOM))),
+        task_tag.clone(),
+        None,
+    );
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::BudgetExceeded {
+            remaining: Asset::native(DENOM, 0u128).to_string()
+        }
+        .to_string()
+    );
+
+    // Anyone can enforce budgets, tasks within budget are kept
+    module_contract.set_sender(&Addr::unchecked("anyone"));
+    module_contract.enforce_budgets(None, None)?;
+    let active_tasks_by_creator_response: ActiveTasksByCreatorResponse =
+        module_contract.active_tasks_by_creator(creator_addr.clone(), None, None, None)?;
+    assert_eq!(
+        active_tasks_by_creator_response.unchecked(),
+        vec![task_tag.clone()]
+    );
+
+    // Lowered cap puts the task over budget
+    module_contract.set_sender(&account.manager.address()?);
+    module_contract.set_budget(
+        task_tag.clone(),
+        Some(AssetListUnchecked::from(AssetList::from(coins(
+            40_000, DENOM,
+        )))),
+    )?;
+    let proxy_balance1 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    module_contract.set_sender(&Addr::unchecked("anyone"));
+    module_contract.enforce_budgets(None, None)?;
+    let active_tasks_by_creator_response: ActiveTasksByCreatorResponse =
+        module_contract.active_tasks_by_creator(creator_addr, None, None, None)?;
+    assert!(active_tasks_by_creator_response.unchecked().is_empty());
+    let proxy_balance2 = mock.query_balance(&account.proxy.address()?, DENOM)?;
+    assert_eq!(proxy_balance2, proxy_balance1 + Uint128::new(46_000));
+    Ok(())
+}
+
+#[test]
+fn admin_acts_for_creator() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        mut module_contract,
+        account,
+        ..
+    } = setup()?;
+
+    let task = |recipient: &str| TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: BankMsg::Send {
+                to_address: recipient.to_owned(),
+                amount: coins(420, DENOM),
+            }
+            .into(),
+            gas_limit: None,
+        }],
+        queries: None,
+        transforms: None,
+        cw20: None,
+    };
+    let assets = AssetListUnchecked::from(AssetList::from(coins(45_000, DENOM)));
+
+    // App itself acts as the installed creator module
+    let creator_addr = module_contract.address()?;
+    module_contract.set_sender(&creator_addr);
+    module_contract.create_task(
+        assets.clone(),
+        Box::new(task("alice")),
+        "first".to_owned(),
+        None,
+        None,
+        &[],
+    )?;
+    module_contract.create_task(
+        assets.clone(),
+        Box::new(task("bob")),
+        "second".to_owned(),
+        None,
+        None,
+        &[],
+    )?;
+
+    // Only admin can pass the creator
+    let err = module_contract.remove_task("first".to_owned(), Some(creator_addr.to_string()));
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        cw_controllers::AdminError::NotAdmin {}.to_string()
+    );
+
+    module_contract.set_sender(&account.manager.address()?);
+    module_contract.refill_task(
+        AssetListUnchecked::from(AssetList::from(coins(100, DENOM))),
+        "first".to_owned(),
+        Some(creator_addr.to_string()),
+    )?;
+    let task_balance: TaskBalanceResponse =
+        module_contract.task_balance(creator_addr.to_string(), "first".to_owned())?;
+    assert_eq!(
+        task_balance.balance.unwrap().native_balance,
+        Uint128::new(45_100)
+    );
+
+    module_contract.remove_task("first".to_owned(), Some(creator_addr.to_string()))?;
+    module_contract.purge(
+        true,
+        vec!["second".to_owned()],
+        Some(creator_addr.to_string()),
+    )?;
+    let active_tasks_by_creator_response: ActiveTasksByCreatorResponse =
+        module_contract.active_tasks_by_creator(creator_addr.to_string(), None, None, None)?;
+    assert!(active_tasks_by_creator_response.unchecked().is_empty());
+    Ok(())
+}
+
+#[test]
+fn sponsor() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        mut module_contract,
+        account,
+        cw20_addr,
+        mock,
+        ..
+    } = setup()?;
+
+    let cw20_amount = Cw20Coin {
+        address: cw20_addr.to_string(),
+        amount: Uint128::new(20),
+    };
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: WasmMsg::Execute {
+                contract_addr: cw20_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "bob".to_owned(),
+                    amount: Uint128::new(20),
+                })?,
+                funds: vec![],
+            }
+            .into(),
+            gas_limit: Some(120),
+        }],
+        queries: None,
+        transforms: None,
+        cw20: Some(cw20_amount),
+    };
+    let task_tag = "test_tag".to_owned();
+    let creator_addr = account.manager.addr_str()?;
+    let assets = {
+        let mut assets = AssetList::from(coins(40_000, DENOM));
+        assets.add(&Asset::cw20(
+            Addr::unchecked(cw20_addr.clone()),
+            Uint128::new(20),
+        ))?;
+        AssetListUnchecked::from(assets)
+    };
+    module_contract.create_task(assets, Box::new(task), task_tag.clone(), None, None, &[])?;
+
+    // Sponsor with native funds
+    let sponsor = Addr::unchecked("sponsor");
+    mock.set_balance(&sponsor, coins(1_000, DENOM))?;
+    module_contract.set_sender(&sponsor);
+    let err = module_contract.sponsor(creator_addr.clone(), task_tag.clone(), &[]);
+    assert_eq!(
+        err.unwrap_err().root().to_string(),
+        AppError::NoSponsorFunds {}.to_string()
+    );
+    module_contract.sponsor(creator_addr.clone(), task_tag.clone(), &coins(1_000, DENOM))?;
+
+    // Sponsor with cw20 through the receive hook
+    mock.app.borrow_mut().execute_contract(
+        account.proxy.address()?,
+        cw20_addr.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: sponsor.to_string(),
+            amount: Uint128::new(10),
+        },
+        &[],
+    )?;
+    mock.app.borrow_mut().execute_contract(
+        sponsor.clone(),
+        cw20_addr.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: module_contract.addr_str()?,
+            amount: Uint128::new(10),
+            msg: to_binary(&AppReceiveMsg::Sponsor {
+                creator_addr: creator_addr.clone(),
+                task_tag: task_tag.clone(),
+            })?,
+        },
+        &[],
+    )?;
+
+    let task_balance: TaskBalanceResponse =
+        module_contract.task_balance(creator_addr.clone(), task_tag.clone())?;
+    assert_eq!(
+        task_balance.balance.unwrap(),
+        TaskBalance {
+            native_balance: Uint128::new(41_000),
+            cw20_balance: Some(Cw20CoinVerified {
+                address: Addr::unchecked(cw20_addr.clone()),
+                amount: Uint128::new(30)
+            }),
+            ibc_balance: None
+        }
+    );
+
+    // Fake cw20 can't record sponsorships
+    let fake_cw20 = Addr::unchecked("fake_cw20");
+    let err = mock.app.borrow_mut().execute_contract(
+        fake_cw20.clone(),
+        module_contract.address()?,
+        &ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: sponsor.to_string(),
+            amount: Uint128::new(1_000_000),
+            msg: to_binary(&AppReceiveMsg::Sponsor {
+                creator_addr: creator_addr.clone(),
+                task_tag: task_tag.clone(),
+            })?,
+        }),
+        &[],
+    );
+    assert_eq!(
+        err.unwrap_err().root_cause().to_string(),
+        AppError::Cw20NotWhitelisted {
+            cw20_addr: fake_cw20.to_string()
+        }
+        .to_string()
+    );
+
+    let sponsorships_response: SponsorshipsResponse =
+        module_contract.sponsorships(creator_addr, task_tag, None, None)?;
+    let mut sponsored = AssetList::from(coins(1_000, DENOM));
+    sponsored.add(&Asset::cw20(cw20_addr, Uint128::new(10)))?;
+    assert_eq!(
+        sponsorships_response.sponsorships,
+        vec![(sponsor, sponsored)]
+    );
+    Ok(())
+}
+
+#[test]
+fn module_funded_task() -> anyhow::Result<()> {
+    // Set up the environment and contract
+    let TestingSetup {
+        mut module_contract,
+        account,
+        cw20_addr,
+        mock,
+        ..
+    } = setup()?;
+
+    let task = TaskRequest {
+        interval: croncat_sdk_tasks::types::Interval::Once,
+        boundary: None,
+        stop_on_fail: false,
+        actions: vec![Action {
+            msg: WasmMsg::Execute {
+                contract_addr: cw20_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "bob".to_owned(),
+                    amount: Uint128::new(20),
+                })?,
+                funds: vec![],
+            }
+            .into(),
+            gas_limit: Some(120),
+        }],
+        queries: None,
+        transforms: None,
+        cw20: Some(Cw20Coin {
+            address: cw20_addr.to_string(),
+            amount: Uint128::new(20),
+        }),
+    };
+
+    // App itself acts as the installed creator module holding its own balance
+    let creator_addr = module_contract.address()?;
+    module_contract.set_sender(&creator_addr);
+    mock.set_balance(&creator_addr, coins(40_000, DENOM))?;
+    mock.app.borrow_mut().execute_contract(
+        account.proxy.address()?,
+        cw20_addr.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: creator_addr.to_string(),
+            amount: Uint128::new(20),
+        },
+        &[],
+    )?;
+
+    // Native funds attached, cw20 taken from the proxy
+    let proxy_cw20 = AssetList::from(vec![Asset::cw20(cw20_addr.clone(), Uint128::new(20))]);
+    module_contract.create_task(
+        AssetListUnchecked::from(proxy_cw20),
+        Box::new(task.clone()),
+        "native".to_owned(),
+        None,
+        None,
+        &coins(40_000, DENOM),
+    )?;
+    let task_balance: TaskBalanceResponse =
+        module_contract.task_balance(creator_addr.to_string(), "native".to_owned())?;
+    assert_eq!(
+        task_balance.balance.unwrap().native_balance,
+        Uint128::new(40_000)
+    );
+    assert!(mock.query_balance(&creator_addr, DENOM)?.is_zero());
+
+    // Cw20 sent in, native taken from the proxy
+    let mut task = task;
+    task.actions[0].msg = WasmMsg::Execute {
+        contract_addr: cw20_addr.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: "alice".to_owned(),
+            amount: Uint128::new(20),
+        })?,
+        funds: vec![],
+    }
+    .into();
+    mock.app.borrow_mut().execute_contract(
+        creator_addr.clone(),
+        cw20_addr.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: creator_addr.to_string(),
+            amount: Uint128::new(20),
+            msg: to_binary(&AppReceiveMsg::CreateTask {
+                task: Box::new(task.clone()),
+                task_tag: "cw20".to_owned(),
+                assets: AssetListUnchecked::from(AssetList::from(coins(40_000, DENOM))),
+                mode: None,
+                group: None,
+            })?,
+        },
+        &[],
+    )?;
+    let task_balance: TaskBalanceResponse =
+        module_contract.task_balance(creator_addr.to_string(), "cw20".to_owned())?;
+    assert_eq!(
+        task_balance.balance.unwrap(),
+        TaskBalance {
+            native_balance: Uint128::new(40_000),
+            cw20_balance: Some(Cw20CoinVerified {
+                address: cw20_addr.clone(),
+                amount: Uint128::new(20)
+            }),
+            ibc_balance: None
+        }
+    );
+    let task_cost: TaskCostResponse =
+        module_contract.task_cost(creator_addr.to_string(), "cw20".to_owned())?;
+    let mut deposited = AssetList::from(coins(40_000, DENOM));
+    deposited.add(&Asset::cw20(cw20_addr.clone(), Uint128::new(20)))?;
+    assert_eq!(task_cost.deposited, deposited);
+
+    // Fake cw20 can't create tasks as the module with proxy funds
+    let fake_cw20 = Addr::unchecked("fake_cw20");
+    let err = mock.app.borrow_mut().execute_contract(
+        fake_cw20.clone(),
+        creator_addr.clone(),
+        &ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: creator_addr.to_string(),
+            amount: Uint128::new(20),
+            msg: to_binary(&AppReceiveMsg::CreateTask {
+                task: Box::new(task),
+                task_tag: "cw20".to_owned(),
+                assets: AssetListUnchecked::from(AssetList::from(coins(40_000, DENOM))),
+                mode: Some(CreationMode::Replace),
+                group: None,
+            })?,
+        }),
+        &[],
+    );
+    assert_eq!(
+        err.unwrap_err().root_cause().to_string(),
+        AppError::Cw20NotWhitelisted {
+            cw20_addr: fake_cw20.to_string()
+        }
+        .to_string()
+    );
+    assert!(module_contract
+        .task_info(creator_addr.to_string(), "cw20".to_owned())
+        .is_ok());
     Ok(())
 }